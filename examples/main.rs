@@ -5,13 +5,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     dotenv::dotenv()?;
     tracing_subscriber::fmt::init();
     // Инициализация клиента
-    let client = reqwest::Client::new();
-    let api = YandexPayApi::new(
-        "https://sandbox.pay.yandex.ru".into(),
-        // Ваш API-ключ для sandbox он совпадает с MERCHANT_ID
-        std::env::var("YANDEX_MERCHANT_ID")?.into(),
-        client,
-    );
+    // Окружение берётся из YANDEX_PAY_ENV (по умолчанию sandbox),
+    // ключ API для sandbox совпадает с YANDEX_MERCHANT_ID
+    let api = YandexPayApi::from_env(reqwest::Client::new())?;
 
     let nid = || uuid::Uuid::now_v7().to_string();
 
@@ -22,10 +18,14 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 .product_id(nid())
                 .quantity(ItemQuantity::new().count(1.0).available(5.0).build())
                 .title("Телефон")
-                .total(29999.99)
+                .total(Money::from_kopecks(2_999_999))
                 .build(),
         ])
-        .total(CartTotal::new().amount(29999.99).build())
+        .total(
+            CartTotal::new()
+                .amount(Money::from_kopecks(2_999_999))
+                .build(),
+        )
         .external_id(nid())
         .build();
 
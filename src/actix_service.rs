@@ -0,0 +1,180 @@
+//! Интеграция с actix-web: готовые сервисы для уведомлений и запросов Yandex Pay к продавцу.
+
+use actix_web::body::BoxBody;
+use actix_web::http::StatusCode;
+use actix_web::http::header::ContentType;
+use actix_web::{HttpRequest, HttpResponse, Responder, web};
+use bytes::Bytes;
+
+use crate::*;
+
+impl Responder for MerchantResponse {
+    type Body = BoxBody;
+
+    fn respond_to(self, _: &HttpRequest) -> HttpResponse {
+        let status = StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        HttpResponse::build(status)
+            .content_type(ContentType::json())
+            .body(self.body)
+    }
+}
+
+/// Сервис `POST /v1/webhook`, передающий уведомления в [`WebhookHandler`].
+///
+/// Возвращает функцию для [`actix_web::App::configure`]. Диспетчер создаётся один раз
+/// и разделяется между воркерами сервера.
+///
+/// ```no_run
+/// # use yandex_pay_api::*;
+/// # use actix_web::{App, HttpServer};
+/// struct Shop;
+///
+/// impl WebhookHandler for Shop {
+///     async fn on_operation_status_updated(
+///         &self,
+///         operation: OperationWebhookData,
+///         context: WebhookContext,
+///     ) -> Result<(), MerchantError> {
+///         println!("{} -> {:?} ({} bytes)", operation.operation_id, operation.status, context.raw_body.len());
+///         Ok(())
+///     }
+/// }
+///
+/// # async fn run(verifier: WebhookVerifier) -> std::io::Result<()> {
+/// let webhooks = webhook_service(WebhookDispatcher::new(verifier, Shop));
+/// HttpServer::new(move || App::new().configure(webhooks.clone()))
+///     .bind("127.0.0.1:8080")?
+///     .run()
+///     .await
+/// # }
+/// ```
+pub fn webhook_service<H>(
+    dispatcher: WebhookDispatcher<H>,
+) -> impl Fn(&mut web::ServiceConfig) + Clone + Send + 'static
+where
+    H: WebhookHandler + Send + Sync + 'static,
+{
+    let dispatcher = web::Data::new(dispatcher);
+    move |config| {
+        config.service(
+            web::resource("/v1/webhook")
+                .app_data(dispatcher.clone())
+                .route(web::post().to(handle_webhook::<H>)),
+        );
+    }
+}
+
+/// Сервисы `POST /v1/order/render`, `/v1/order/create` и `/v1/webhook` для [`MerchantHandler`].
+pub fn merchant_service<H>(
+    dispatcher: MerchantDispatcher<H>,
+) -> impl Fn(&mut web::ServiceConfig) + Clone + Send + 'static
+where
+    H: MerchantHandler + Send + Sync + 'static,
+{
+    let dispatcher = web::Data::new(dispatcher);
+    move |config| {
+        for path in ["/v1/order/render", "/v1/order/create", "/v1/webhook"] {
+            config.service(
+                web::resource(path)
+                    .app_data(dispatcher.clone())
+                    .route(web::post().to(handle_merchant::<H>)),
+            );
+        }
+    }
+}
+
+async fn handle_webhook<H>(
+    dispatcher: web::Data<WebhookDispatcher<H>>,
+    body: Bytes,
+) -> MerchantResponse
+where
+    H: WebhookHandler + Send + Sync + 'static,
+{
+    dispatcher.dispatch(body).await
+}
+
+async fn handle_merchant<H>(
+    dispatcher: web::Data<MerchantDispatcher<H>>,
+    request: HttpRequest,
+    body: Bytes,
+) -> MerchantResponse
+where
+    H: MerchantHandler + Send + Sync + 'static,
+{
+    dispatcher.dispatch(request.path(), &body).await
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{App, test};
+
+    use super::*;
+    use crate::merchant::tests::{RecordingShop, cart_json, fresh_token, order_event, verifier};
+
+    fn post_token(uri: &str, payload: serde_json::Value) -> (String, test::TestRequest) {
+        let token = fresh_token(payload);
+        let request = test::TestRequest::post()
+            .uri(uri)
+            .set_payload(token.clone());
+        (token, request)
+    }
+
+    #[actix_web::test]
+    async fn test_webhook_service() {
+        let shop = RecordingShop::default();
+        let app = test::init_service(App::new().configure(webhook_service(
+            WebhookDispatcher::new(verifier(), shop.clone()),
+        )))
+        .await;
+
+        let (token, request) = post_token("/v1/webhook", order_event("order-1"));
+        let response = test::call_service(&app, request.to_request()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response
+                .headers()
+                .get(actix_web::http::header::CONTENT_TYPE)
+                .unwrap(),
+            "application/json"
+        );
+        assert_eq!(
+            *shop.orders.lock().unwrap(),
+            vec![("order-1".to_string(), Bytes::from(token))]
+        );
+
+        let (_, request) = post_token("/v1/order/render", order_event("order-1"));
+        let response = test::call_service(&app, request.to_request()).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[actix_web::test]
+    async fn test_merchant_service() {
+        let app = test::init_service(App::new().configure(merchant_service(
+            MerchantDispatcher::new(verifier(), RecordingShop::default()),
+        )))
+        .await;
+        let cart = cart_json();
+
+        let (_, request) = post_token(
+            "/v1/order/render",
+            serde_json::json!({"cart": cart, "currencyCode": "RUB"}),
+        );
+        let response = test::call_service(&app, request.to_request()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let json: serde_json::Value = test::read_body_json(response).await;
+        assert_eq!(json["data"]["cart"]["items"][0]["productId"], "p1");
+
+        let (_, request) = post_token(
+            "/v1/order/create",
+            serde_json::json!({"cart": cart, "currencyCode": "RUB", "orderAmount": "1.00"}),
+        );
+        let response = test::call_service(&app, request.to_request()).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let json: serde_json::Value = test::read_body_json(response).await;
+        assert_eq!(json["reasonCode"], "ORDER_DETAILS_MISMATCH");
+
+        let (_, request) = post_token("/v1/webhook", order_event("order-1"));
+        let response = test::call_service(&app, request.to_request()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
@@ -0,0 +1,161 @@
+//! Интеграция с axum: готовые маршруты для уведомлений и запросов Yandex Pay к продавцу.
+
+use std::sync::Arc;
+
+use axum::Router;
+use axum::extract::State;
+use axum::http::{StatusCode, Uri, header};
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use bytes::Bytes;
+
+use crate::*;
+
+impl IntoResponse for MerchantResponse {
+    fn into_response(self) -> Response {
+        let status = StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        (
+            status,
+            [(header::CONTENT_TYPE, "application/json")],
+            self.body,
+        )
+            .into_response()
+    }
+}
+
+/// Маршрут `POST /v1/webhook`, передающий уведомления в [`WebhookHandler`].
+///
+/// ```no_run
+/// # use yandex_pay_api::*;
+/// struct Shop;
+///
+/// impl WebhookHandler for Shop {
+///     async fn on_order_status_updated(
+///         &self,
+///         order: OrderWebhookData,
+///         context: WebhookContext,
+///     ) -> Result<(), MerchantError> {
+///         println!("{} -> {:?} ({} bytes)", order.order_id, order.payment_status, context.raw_body.len());
+///         Ok(())
+///     }
+/// }
+///
+/// # fn app(verifier: WebhookVerifier) -> axum::Router {
+/// webhook_router(WebhookDispatcher::new(verifier, Shop))
+/// # }
+/// ```
+pub fn webhook_router<H>(dispatcher: WebhookDispatcher<H>) -> Router
+where
+    H: WebhookHandler + Send + Sync + 'static,
+{
+    Router::new()
+        .route("/v1/webhook", post(handle_webhook::<H>))
+        .with_state(Arc::new(dispatcher))
+}
+
+/// Маршруты `POST /v1/order/render`, `/v1/order/create` и `/v1/webhook` для [`MerchantHandler`].
+pub fn merchant_router<H>(dispatcher: MerchantDispatcher<H>) -> Router
+where
+    H: MerchantHandler + Send + Sync + 'static,
+{
+    Router::new()
+        .route("/v1/order/render", post(handle_merchant::<H>))
+        .route("/v1/order/create", post(handle_merchant::<H>))
+        .route("/v1/webhook", post(handle_merchant::<H>))
+        .with_state(Arc::new(dispatcher))
+}
+
+async fn handle_webhook<H>(
+    State(dispatcher): State<Arc<WebhookDispatcher<H>>>,
+    body: Bytes,
+) -> MerchantResponse
+where
+    H: WebhookHandler + Send + Sync + 'static,
+{
+    dispatcher.dispatch(body).await
+}
+
+async fn handle_merchant<H>(
+    State(dispatcher): State<Arc<MerchantDispatcher<H>>>,
+    uri: Uri,
+    body: Bytes,
+) -> MerchantResponse
+where
+    H: MerchantHandler + Send + Sync + 'static,
+{
+    dispatcher.dispatch(uri.path(), &body).await
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::Body;
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::merchant::tests::{RecordingShop, cart_json, fresh_token, order_event, verifier};
+
+    fn post_token(uri: &str, payload: serde_json::Value) -> (String, Request<Body>) {
+        let token = fresh_token(payload);
+        let request = Request::post(uri).body(Body::from(token.clone())).unwrap();
+        (token, request)
+    }
+
+    async fn json(response: Response) -> serde_json::Value {
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_webhook_router() {
+        let shop = RecordingShop::default();
+        let app = webhook_router(WebhookDispatcher::new(verifier(), shop.clone()));
+
+        let (token, request) = post_token("/v1/webhook", order_event("order-1"));
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "application/json");
+        assert_eq!(
+            *shop.orders.lock().unwrap(),
+            vec![("order-1".to_string(), Bytes::from(token))]
+        );
+
+        let (_, request) = post_token("/v1/order/render", order_event("order-1"));
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_merchant_router() {
+        let app = merchant_router(MerchantDispatcher::new(
+            verifier(),
+            RecordingShop::default(),
+        ));
+        let cart = cart_json();
+
+        let (_, request) = post_token(
+            "/v1/order/render",
+            serde_json::json!({"cart": cart, "currencyCode": "RUB"}),
+        );
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            json(response).await["data"]["cart"]["items"][0]["productId"],
+            "p1"
+        );
+
+        let (_, request) = post_token(
+            "/v1/order/create",
+            serde_json::json!({"cart": cart, "currencyCode": "RUB", "orderAmount": "1.00"}),
+        );
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(json(response).await["reasonCode"], "ORDER_DETAILS_MISMATCH");
+
+        let (_, request) = post_token("/v1/webhook", order_event("order-1"));
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
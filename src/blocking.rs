@@ -0,0 +1,355 @@
+//! Блокирующий клиент для кода без асинхронного рантайма.
+//!
+//! Запросы формируются и ответы разбираются тем же кодом, что и в [`YandexPayApi`].
+
+use std::time::Duration;
+
+use crate::*;
+
+pub trait BlockingHttpClient: Clone {
+    fn send<T: serde::de::DeserializeOwned>(&self, request: YandexPayApiRequest) -> R<T>;
+}
+
+impl BlockingHttpClient for reqwest::blocking::Client {
+    fn send<T: serde::de::DeserializeOwned>(&self, request: YandexPayApiRequest) -> R<T> {
+        let method = match request.method {
+            Method::Get => reqwest::Method::GET,
+            Method::Post => reqwest::Method::POST,
+        };
+        let mut request_builder = self
+            .request(method, &*request.url)
+            .query(&request.query)
+            .timeout(Duration::from_millis(request.request_timeout.into()));
+        request_builder = request_builder.headers(reqwest_headers(&request)?);
+        if let Some(body) = request.body {
+            request_builder = request_builder.body(body);
+        }
+        let response = request_builder.send()?;
+        let status = response.status().as_u16();
+        let body = response.bytes()?;
+        decode_response(status, &body)
+    }
+}
+
+/// Синхронный аналог [`YandexPayApi`] с теми же методами.
+///
+/// ```no_run
+/// # use yandex_pay_api::*;
+/// let api = BlockingYandexPayApi::new(
+///     "https://sandbox.pay.yandex.ru".into(),
+///     "merchant-id".into(),
+///     reqwest::blocking::Client::new(),
+/// );
+/// let order = api.get_order("order-1")?;
+/// # Ok::<(), YandexPayApiError>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct BlockingYandexPayApi<C: BlockingHttpClient = reqwest::blocking::Client> {
+    pub client: C,
+    pub base_url: S,
+    pub api_key: SecretKey,
+    pub retry_policy: Option<RetryPolicy>,
+    /// Таймаут запроса, если не задан — используется значение по умолчанию `YandexPayApiRequest`
+    pub request_timeout: Option<Duration>,
+    /// Проверять ли запросы на создание заказов и подписок через [`Validate`] перед отправкой
+    pub validate_requests: bool,
+}
+
+impl<C: BlockingHttpClient> BlockingYandexPayApi<C> {
+    pub fn new(base_url: S, api_key: SecretKey, client: C) -> Self {
+        BlockingYandexPayApi {
+            client,
+            base_url,
+            api_key,
+            retry_policy: None,
+            request_timeout: None,
+            validate_requests: false,
+        }
+    }
+
+    /// См. [`YandexPayApi::with_validation`]
+    pub fn with_validation(mut self, validate_requests: bool) -> Self {
+        self.validate_requests = validate_requests;
+        self
+    }
+
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(retry_policy);
+        self
+    }
+
+    /// См. [`YandexPayApi::with_request_timeout`]
+    pub fn with_request_timeout(&self, request_timeout: Duration) -> Self {
+        BlockingYandexPayApi {
+            request_timeout: Some(request_timeout),
+            ..self.clone()
+        }
+    }
+
+    pub fn get_base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    pub fn get_api_key(&self) -> &SecretKey {
+        &self.api_key
+    }
+
+    fn endpoints(&self) -> Endpoints<'_> {
+        Endpoints {
+            base_url: &self.base_url,
+            api_key: &self.api_key,
+            validate_requests: self.validate_requests,
+        }
+    }
+
+    fn send<T: serde::de::DeserializeOwned>(&self, mut request: YandexPayApiRequest) -> R<T> {
+        apply_timeout(&mut request, self.request_timeout);
+        let Some(policy) = &self.retry_policy else {
+            return self.client.send(request);
+        };
+        loop {
+            let result = self.client.send(request.clone());
+            match result
+                .as_ref()
+                .err()
+                .and_then(|err| policy.next_delay(&request, err))
+            {
+                Some(delay) => {
+                    std::thread::sleep(delay);
+                    request.request_attempt += 1;
+                }
+                None => return result,
+            }
+        }
+    }
+}
+
+/// Yandex Pay API
+impl<C: BlockingHttpClient> BlockingYandexPayApi<C> {
+    /// См. [`YandexPayApi::create_order`]
+    pub fn create_order(&self, request: CreateOrderRequest) -> R<CreateOrderResponse> {
+        self.send(self.endpoints().create_order(&request)?)
+    }
+
+    /// См. [`YandexPayApi::get_order`]
+    pub fn get_order(&self, order_id: impl Into<String>) -> R<OrderResponseData> {
+        self.send(self.endpoints().get_order(&order_id.into())?)
+    }
+
+    /// См. [`YandexPayApi::cancel_order`]
+    pub fn cancel_order(
+        &self,
+        order_id: impl Into<String>,
+        request: CancelOrderRequest,
+    ) -> R<OperationResponseData> {
+        self.send(self.endpoints().cancel_order(&order_id.into(), &request)?)
+    }
+
+    /// См. [`YandexPayApi::refund_order`]
+    pub fn refund_order(
+        &self,
+        order_id: impl Into<String>,
+        request: RefundRequest,
+    ) -> R<OperationResponseData> {
+        self.send(self.endpoints().refund_order(&order_id.into(), &request)?)
+    }
+
+    /// См. [`YandexPayApi::capture_order`]
+    pub fn capture_order(
+        &self,
+        order_id: impl Into<String>,
+        request: CaptureOrderRequest,
+    ) -> R<OperationResponseData> {
+        self.send(self.endpoints().capture_order(&order_id.into(), &request)?)
+    }
+
+    /// См. [`YandexPayApi::rollback_order`]
+    pub fn rollback_order(&self, order_id: impl Into<String>) -> R<serde_json::Value> {
+        self.send(self.endpoints().rollback_order(&order_id.into())?)
+    }
+
+    /// См. [`YandexPayApi::submit_order`]
+    pub fn submit_order(
+        &self,
+        order_id: impl Into<String>,
+        request: SubmitRequest,
+    ) -> R<OperationResponseData> {
+        self.send(self.endpoints().submit_order(&order_id.into(), &request)?)
+    }
+
+    /// См. [`YandexPayApi::get_operation`]
+    pub fn get_operation(
+        &self,
+        external_operation_id: impl Into<String>,
+    ) -> R<OperationResponseData> {
+        self.send(
+            self.endpoints()
+                .get_operation(&external_operation_id.into())?,
+        )
+    }
+
+    /// См. [`YandexPayApi::create_subscription`]
+    pub fn create_subscription(
+        &self,
+        subscription: CreateSubscriptionRequest,
+    ) -> R<CreateSubscriptionResponseData> {
+        self.send(self.endpoints().create_subscription(&subscription)?)
+    }
+
+    /// См. [`YandexPayApi::recur_subscription`]
+    pub fn recur_subscription(
+        &self,
+        subscription: CreateRecurrentChargeRequest,
+    ) -> R<RecurSubscriptionResponseData> {
+        self.send(self.endpoints().recur_subscription(&subscription)?)
+    }
+
+    /// См. [`YandexPayApi::get_subscription`]
+    pub fn get_subscription(
+        &self,
+        customer_subscription_id: impl Into<String>,
+        request: GetSubscriptionRequest,
+    ) -> R<CustomerSubscriptionResponseData> {
+        self.send(
+            self.endpoints()
+                .get_subscription(&customer_subscription_id.into(), &request)?,
+        )
+    }
+
+    /// См. [`YandexPayApi::cancel_subscription`]
+    pub fn cancel_subscription(
+        &self,
+        customer_subscription_id: impl Into<String>,
+    ) -> R<CustomerSubscriptionResponseData> {
+        self.send(
+            self.endpoints()
+                .cancel_subscription(&customer_subscription_id.into())?,
+        )
+    }
+
+    /// См. [`YandexPayApi::list_subscription_charges`]
+    pub fn list_subscription_charges(
+        &self,
+        customer_subscription_id: impl Into<String>,
+        request: ListSubscriptionChargesRequest,
+    ) -> R<SubscriptionChargesResponseData> {
+        self.send(
+            self.endpoints()
+                .list_subscription_charges(&customer_subscription_id.into(), &request)?,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+
+    use super::*;
+
+    /// Принимает одно соединение, отвечает `status` с телом `body` и возвращает полученный запрос
+    fn serve_once(status: u16, body: &'static str) -> (String, std::thread::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            let (mut socket, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(socket.try_clone().unwrap());
+            let mut head = String::new();
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if let Some(value) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                    content_length = value.trim().parse().unwrap();
+                }
+                if line == "\r\n" {
+                    break;
+                }
+                head.push_str(&line);
+            }
+            let mut request_body = vec![0; content_length];
+            reader.read_exact(&mut request_body).unwrap();
+            write!(
+                socket,
+                "HTTP/1.1 {status} X\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            )
+            .unwrap();
+            head + &String::from_utf8(request_body).unwrap()
+        });
+        (base_url, server)
+    }
+
+    #[test]
+    fn test_blocking_get_operation() {
+        let (base_url, server) = serve_once(
+            200,
+            r#"{"status": "success", "data": {"operation": {"amount": "10.00", "operationId": "op", "operationType": "REFUND", "orderId": "order-1", "status": "SUCCESS", "externalOperationId": "ext-1"}}}"#,
+        );
+        let api = BlockingYandexPayApi::new(
+            base_url.into(),
+            "key".into(),
+            reqwest::blocking::Client::new(),
+        );
+        let response = api.get_operation("ext-1").unwrap();
+        assert_eq!(response.operation.order_id, "order-1");
+        let request = server.join().unwrap();
+        assert!(request.starts_with("GET /api/merchant/v1/operations/ext-1 "));
+        assert!(request.contains("authorization: Api-Key key\r\n"));
+        assert!(request.contains("x-request-attempt: 0\r\n"));
+    }
+
+    #[test]
+    fn test_blocking_get_subscription_query() {
+        let (base_url, server) = serve_once(
+            200,
+            r#"{"status": "success", "data": {"status": "ACTIVE", "subscriptionPlanId": "plan", "customerSubscriptionId": "sub-1", "isCardActive": true}}"#,
+        );
+        let api = BlockingYandexPayApi::new(
+            base_url.into(),
+            "key".into(),
+            reqwest::blocking::Client::new(),
+        );
+        let response = api
+            .get_subscription(
+                "sub-1",
+                GetSubscriptionRequest::new()
+                    .check_card_active(true)
+                    .build(),
+            )
+            .unwrap();
+        assert_eq!(response.status, SubscriptionStatus::Active);
+        let request = server.join().unwrap();
+        assert!(
+            request.starts_with(
+                "GET /api/merchant/v1/subscriptions/sub-1?checkCardActive=true HTTP/1.1\r\n"
+            ),
+            "{request}"
+        );
+        assert!(!request.to_ascii_lowercase().contains("content-length"));
+    }
+
+    #[test]
+    fn test_blocking_api_error() {
+        let (base_url, server) = serve_once(
+            400,
+            r#"{"status": "fail", "reasonCode": "ORDER_NOT_FOUND", "reason": "no such order"}"#,
+        );
+        let api = BlockingYandexPayApi::new(
+            base_url.into(),
+            "key".into(),
+            reqwest::blocking::Client::new(),
+        );
+        let err = api
+            .cancel_order("order-1", CancelOrderRequest::new().reason("test").build())
+            .unwrap_err();
+        let YandexPayApiError::Api(err) = err else {
+            panic!("{err:?}");
+        };
+        assert_eq!(err.http_status, Some(400));
+        assert_eq!(err.reason.as_deref(), Some("no such order"));
+        let request = server.join().unwrap();
+        assert!(request.starts_with("POST /api/merchant/v1/orders/order-1/cancel "));
+        assert!(request.contains(r#""reason":"test""#));
+    }
+}
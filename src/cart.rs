@@ -0,0 +1,352 @@
+use std::fmt;
+
+use crate::*;
+
+/// Расхождение между указанной и вычисленной суммой
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CartTotalMismatch {
+    /// Путь к полю, например `items[0].total`
+    pub path: String,
+    /// Сумма, вычисленная по ценам и количеству
+    pub expected: Money,
+    /// Сумма, указанная в корзине
+    pub actual: Money,
+}
+
+impl fmt::Display for CartTotalMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: expected {}, got {}",
+            self.path, self.expected, self.actual
+        )
+    }
+}
+
+/// Все расхождения сумм в корзине
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub struct CartTotalsError {
+    pub mismatches: Vec<CartTotalMismatch>,
+}
+
+impl fmt::Display for CartTotalsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Cart totals mismatch: ")?;
+        for (i, mismatch) in self.mismatches.iter().enumerate() {
+            if i > 0 {
+                write!(f, "; ")?;
+            }
+            write!(f, "{}", mismatch)?;
+        }
+        Ok(())
+    }
+}
+
+/// Вычисление и проверка сумм корзины.
+///
+/// Сумма позиции — `discounted_unit_price * quantity.count` (или `unit_price`, если скидки нет),
+/// `subtotal` — `unit_price * quantity.count`, сумма корзины — сумма позиций за вычетом
+/// скидок корзины (`discounts`), если они есть в модели.
+/// Если у позиции нет цены за единицу, её сумма берётся как есть.
+pub trait CartCalculator {
+    /// Сумма корзины, вычисленная по позициям и скидкам
+    fn calculated_total(&self) -> Money;
+
+    /// Заполняет суммы позиций и корзины по ценам и количеству
+    fn calculate_totals(&mut self);
+
+    /// Сравнивает указанные вручную суммы с вычисленными
+    fn check_totals(&self) -> Result<(), CartTotalsError>;
+}
+
+/// Позиция корзины с ценой за единицу и количеством
+trait CartLine {
+    fn count(&self) -> f64;
+    fn unit_price(&self) -> Option<Money>;
+    fn discounted_unit_price(&self) -> Option<Money>;
+    fn subtotal(&self) -> Option<Money>;
+    fn set_subtotal(&mut self, subtotal: Money);
+    /// Указанная сумма позиции, если она есть в модели
+    fn total(&self) -> Option<Money>;
+    /// Записывает сумму позиции. Для моделей без поля суммы ничего не делает.
+    fn set_total(&mut self, total: Money);
+
+    fn calculated_subtotal(&self) -> Option<Money> {
+        self.unit_price()
+            .map(|price| price.mul_quantity(self.count()))
+    }
+
+    fn calculated_total(&self) -> Option<Money> {
+        self.discounted_unit_price()
+            .or(self.unit_price())
+            .map(|price| price.mul_quantity(self.count()))
+    }
+
+    /// Сумма, которая идёт в итог корзины
+    fn line_total(&self) -> Money {
+        self.calculated_total()
+            .or(self.total())
+            .or(self.subtotal())
+            .unwrap_or_default()
+    }
+
+    fn calculate(&mut self) {
+        if let Some(subtotal) = self.calculated_subtotal() {
+            self.set_subtotal(subtotal);
+        }
+        if let Some(total) = self.calculated_total() {
+            self.set_total(total);
+        }
+    }
+
+    fn check(&self, index: usize, mismatches: &mut Vec<CartTotalMismatch>) {
+        let mut check = |field: &str, expected: Option<Money>, actual: Option<Money>| {
+            if let (Some(expected), Some(actual)) = (expected, actual)
+                && expected != actual
+            {
+                mismatches.push(CartTotalMismatch {
+                    path: format!("items[{index}].{field}"),
+                    expected,
+                    actual,
+                });
+            }
+        };
+        check("subtotal", self.calculated_subtotal(), self.subtotal());
+        check("total", self.calculated_total(), self.total());
+    }
+}
+
+impl CartLine for RenderedCartItem {
+    fn count(&self) -> f64 {
+        self.quantity.count
+    }
+
+    fn unit_price(&self) -> Option<Money> {
+        self.unit_price
+    }
+
+    fn discounted_unit_price(&self) -> Option<Money> {
+        self.discounted_unit_price
+    }
+
+    fn subtotal(&self) -> Option<Money> {
+        self.subtotal
+    }
+
+    fn set_subtotal(&mut self, subtotal: Money) {
+        self.subtotal = Some(subtotal);
+    }
+
+    fn total(&self) -> Option<Money> {
+        Some(self.total)
+    }
+
+    fn set_total(&mut self, total: Money) {
+        self.total = total;
+    }
+}
+
+impl CartLine for CartItemWithoutFinalPriceCamelCase {
+    fn count(&self) -> f64 {
+        self.quantity.count
+    }
+
+    fn unit_price(&self) -> Option<Money> {
+        self.unit_price
+    }
+
+    fn discounted_unit_price(&self) -> Option<Money> {
+        self.discounted_unit_price
+    }
+
+    fn subtotal(&self) -> Option<Money> {
+        self.subtotal
+    }
+
+    fn set_subtotal(&mut self, subtotal: Money) {
+        self.subtotal = Some(subtotal);
+    }
+
+    fn total(&self) -> Option<Money> {
+        None
+    }
+
+    /// У позиции нет поля суммы, записывать нечего
+    fn set_total(&mut self, _total: Money) {}
+}
+
+fn calculated_total<L: CartLine>(items: &[L], discounts: &[Discount]) -> Money {
+    let items: Money = items.iter().map(CartLine::line_total).sum();
+    let discounts: Money = discounts.iter().map(|discount| discount.amount).sum();
+    items - discounts
+}
+
+fn check_totals<L: CartLine>(
+    items: &[L],
+    discounts: &[Discount],
+    total: Money,
+) -> Result<(), CartTotalsError> {
+    let mut mismatches = vec![];
+    for (i, item) in items.iter().enumerate() {
+        item.check(i, &mut mismatches);
+    }
+    let expected = calculated_total(items, discounts);
+    if expected != total {
+        mismatches.push(CartTotalMismatch {
+            path: "total.amount".into(),
+            expected,
+            actual: total,
+        });
+    }
+    if mismatches.is_empty() {
+        Ok(())
+    } else {
+        Err(CartTotalsError { mismatches })
+    }
+}
+
+impl CartCalculator for RenderedCart {
+    fn calculated_total(&self) -> Money {
+        calculated_total(&self.items, &[])
+    }
+
+    fn calculate_totals(&mut self) {
+        self.items.iter_mut().for_each(CartLine::calculate);
+        self.total.amount = self.calculated_total();
+    }
+
+    fn check_totals(&self) -> Result<(), CartTotalsError> {
+        check_totals(&self.items, &[], self.total.amount)
+    }
+}
+
+impl CartCalculator for CartWithRequiredTotalWithoutFinalPrice {
+    fn calculated_total(&self) -> Money {
+        calculated_total(&self.items, &self.discounts)
+    }
+
+    fn calculate_totals(&mut self) {
+        self.items.iter_mut().for_each(CartLine::calculate);
+        self.total.amount = self.calculated_total();
+    }
+
+    fn check_totals(&self) -> Result<(), CartTotalsError> {
+        check_totals(&self.items, &self.discounts, self.total.amount)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(
+        product_id: &str,
+        unit_price: i64,
+        discounted: Option<i64>,
+        count: f64,
+    ) -> RenderedCartItem {
+        RenderedCartItem::new()
+            .product_id(product_id)
+            .quantity(ItemQuantity::new().count(count).available(count).build())
+            .title("Товар")
+            .total(Money::ZERO)
+            .unit_price(Some(Money::from_kopecks(unit_price)))
+            .discounted_unit_price(discounted.map(Money::from_kopecks))
+            .build()
+    }
+
+    fn cart(items: Vec<RenderedCartItem>) -> RenderedCart {
+        RenderedCart::new()
+            .items(items)
+            .total(CartTotal::new().amount(Money::ZERO).build())
+            .external_id("cart")
+            .build()
+    }
+
+    #[test]
+    fn test_calculate_rendered_cart() {
+        let mut cart = cart(vec![
+            item("a", 1000, Some(900), 3.0),
+            item("b", 333, None, 0.5),
+        ]);
+        cart.calculate_totals();
+        assert_eq!(cart.items[0].total, Money::from_kopecks(2700));
+        assert_eq!(cart.items[0].subtotal, Some(Money::from_kopecks(3000)));
+        assert_eq!(cart.items[1].total, Money::from_kopecks(167));
+        assert_eq!(cart.total.amount, Money::from_kopecks(2867));
+        assert_eq!(cart.check_totals(), Ok(()));
+    }
+
+    #[test]
+    fn test_report_mismatches() {
+        let mut cart = cart(vec![
+            item("a", 1000, Some(900), 3.0),
+            item("b", 500, None, 1.0),
+        ]);
+        cart.calculate_totals();
+        cart.items[0].total = Money::from_kopecks(3000);
+        let error = cart.check_totals().unwrap_err();
+        assert_eq!(
+            error.mismatches,
+            vec![CartTotalMismatch {
+                path: "items[0].total".into(),
+                expected: Money::from_kopecks(2700),
+                actual: Money::from_kopecks(3000),
+            }]
+        );
+        cart.total.amount = Money::from_kopecks(1);
+        let error = cart.check_totals().unwrap_err();
+        assert_eq!(error.mismatches.len(), 2);
+        assert_eq!(error.mismatches[1].expected, Money::from_kopecks(3200));
+    }
+
+    #[test]
+    fn test_calculate_capture_cart() {
+        let mut cart = CartWithRequiredTotalWithoutFinalPrice::new()
+            .items(vec![
+                CartItemWithoutFinalPriceCamelCase::new()
+                    .product_id("a")
+                    .quantity(ItemQuantity::new().count(2.0).available(2.0).build())
+                    .unit_price(Some(Money::from_rubles(10)))
+                    .discounted_unit_price(Some(Money::from_rubles(8)))
+                    .build(),
+            ])
+            .total(CartTotal::new().amount(Money::from_rubles(20)).build())
+            .build();
+        let error = cart.check_totals().unwrap_err();
+        assert_eq!(error.mismatches[0].path, "total.amount");
+        assert_eq!(error.mismatches[0].expected, Money::from_rubles(16));
+        cart.calculate_totals();
+        assert_eq!(cart.total.amount, Money::from_rubles(16));
+        assert_eq!(cart.items[0].subtotal, Some(Money::from_rubles(20)));
+        assert_eq!(cart.check_totals(), Ok(()));
+    }
+
+    #[test]
+    fn test_capture_cart_discount() {
+        let mut cart = CartWithRequiredTotalWithoutFinalPrice::new()
+            .items(vec![
+                CartItemWithoutFinalPriceCamelCase::new()
+                    .product_id("a")
+                    .quantity(ItemQuantity::new().count(3.0).available(3.0).build())
+                    .unit_price(Some(Money::from_rubles(10)))
+                    .build(),
+            ])
+            .discounts(vec![
+                Discount::new()
+                    .amount(Money::from_kopecks(250))
+                    .description("Промокод")
+                    .discount_id("promo")
+                    .build(),
+            ])
+            .total(CartTotal::new().amount(Money::from_kopecks(2750)).build())
+            .build();
+        assert_eq!(cart.calculated_total(), Money::from_kopecks(2750));
+        assert_eq!(cart.check_totals(), Ok(()));
+        cart.total.amount = Money::from_rubles(30);
+        let error = cart.check_totals().unwrap_err();
+        assert_eq!(error.mismatches[0].expected, Money::from_kopecks(2750));
+        cart.calculate_totals();
+        assert_eq!(cart.total.amount, Money::from_kopecks(2750));
+    }
+}
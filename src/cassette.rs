@@ -0,0 +1,393 @@
+//! Запись и воспроизведение обменов с API для интеграционных тестов без сети.
+//!
+//! [`RecordingClient`] один раз записывает реальные ответы песочницы в файл-кассету,
+//! [`ReplayClient`] затем отдаёт их в CI.
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::*;
+
+/// Записанные запросы и ответы
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Cassette {
+    pub interactions: Vec<Interaction>,
+}
+
+impl Cassette {
+    pub fn load(path: impl AsRef<Path>) -> R<Self> {
+        let file = std::fs::read(path).map_err(YandexPayApiError::transport)?;
+        Ok(serde_json::from_slice(&file)?)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> R {
+        let file = serde_json::to_vec_pretty(self)?;
+        std::fs::write(path, file).map_err(YandexPayApiError::transport)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Interaction {
+    pub request: RecordedRequest,
+    pub response: RecordedResponse,
+}
+
+/// Запрос без заголовков, URL вместе с параметрами. Ключ API передаётся только в заголовке
+/// `Authorization`, поэтому в кассету не попадает.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedRequest {
+    pub method: Method,
+    pub url: String,
+    #[serde(default)]
+    pub body: Option<serde_json::Value>,
+}
+
+impl RecordedRequest {
+    fn new(request: &YandexPayApiRequest) -> Self {
+        RecordedRequest {
+            method: request.method,
+            url: request.full_url(),
+            body: request.body.as_deref().map(json_or_string),
+        }
+    }
+
+    /// Путь URL вместе с параметрами запроса, без схемы и хоста
+    fn path(&self) -> &str {
+        let url = self
+            .url
+            .split_once("://")
+            .map_or(&*self.url, |(_, rest)| rest);
+        url.find('/').map_or("/", |index| &url[index..])
+    }
+
+    fn matches(&self, other: &RecordedRequest) -> bool {
+        self.method == other.method
+            && self.path() == other.path()
+            && normalize(&self.body) == normalize(&other.body)
+    }
+}
+
+/// Ответ API. Тело, не являющееся JSON, хранится строкой.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedResponse {
+    pub status: u16,
+    pub body: serde_json::Value,
+}
+
+impl RecordedResponse {
+    fn into_raw(self) -> R<RawResponse> {
+        let body = match self.body {
+            serde_json::Value::String(body) => body.into_bytes(),
+            body => serde_json::to_vec(&body)?,
+        };
+        Ok(RawResponse {
+            status: self.status,
+            body: body.into(),
+        })
+    }
+}
+
+fn json_or_string(body: &[u8]) -> serde_json::Value {
+    serde_json::from_slice(body)
+        .unwrap_or_else(|_| serde_json::Value::String(String::from_utf8_lossy(body).into_owned()))
+}
+
+/// Пустое тело и `null` считаются одинаковыми; порядок ключей не важен
+fn normalize(body: &Option<serde_json::Value>) -> &serde_json::Value {
+    body.as_ref().unwrap_or(&serde_json::Value::Null)
+}
+
+/// Транспорт, записывающий каждый обмен в кассету.
+///
+/// Файл перезаписывается после каждого запроса, поэтому кассета сохраняется даже
+/// при падении теста.
+#[derive(Debug, Clone)]
+pub struct RecordingClient<C> {
+    pub inner: C,
+    path: Arc<PathBuf>,
+    cassette: Arc<Mutex<Cassette>>,
+}
+
+impl<C: RawHttpClient> RecordingClient<C> {
+    /// Начинает новую кассету в `path`
+    pub fn new(inner: C, path: impl Into<PathBuf>) -> Self {
+        RecordingClient {
+            inner,
+            path: Arc::new(path.into()),
+            cassette: Default::default(),
+        }
+    }
+
+    pub fn cassette(&self) -> Cassette {
+        self.cassette.lock().unwrap().clone()
+    }
+
+    async fn record(&self, request: YandexPayApiRequest) -> R<RawResponse> {
+        let recorded = RecordedRequest::new(&request);
+        let response = self.inner.send_raw(request).await?;
+        let mut cassette = self.cassette.lock().unwrap();
+        cassette.interactions.push(Interaction {
+            request: recorded,
+            response: RecordedResponse {
+                status: response.status,
+                body: json_or_string(&response.body),
+            },
+        });
+        cassette.save(&*self.path)?;
+        Ok(response)
+    }
+}
+
+impl<C: RawHttpClient + Clone> HttpClient for RecordingClient<C> {
+    async fn send<T: serde::de::DeserializeOwned>(&self, request: YandexPayApiRequest) -> R<T> {
+        self.record(request).await?.decode()
+    }
+}
+
+impl<C: RawHttpClient> RawHttpClient for RecordingClient<C> {
+    fn send_raw(&self, request: YandexPayApiRequest) -> RawFuture<'_> {
+        Box::pin(self.record(request))
+    }
+}
+
+#[derive(Debug)]
+struct ReplayState {
+    interactions: Vec<Interaction>,
+    used: Vec<bool>,
+}
+
+/// Транспорт, отвечающий записанными в кассету ответами.
+///
+/// Запрос сопоставляется с первым неиспользованным обменом по методу, пути и телу JSON.
+/// Если подходящего обмена нет, возвращается [`YandexPayApiError::Transport`] с описанием запроса.
+#[derive(Debug, Clone)]
+pub struct ReplayClient {
+    state: Arc<Mutex<ReplayState>>,
+}
+
+impl ReplayClient {
+    pub fn new(cassette: Cassette) -> Self {
+        let used = vec![false; cassette.interactions.len()];
+        ReplayClient {
+            state: Arc::new(Mutex::new(ReplayState {
+                interactions: cassette.interactions,
+                used,
+            })),
+        }
+    }
+
+    pub fn from_file(path: impl AsRef<Path>) -> R<Self> {
+        Ok(Self::new(Cassette::load(path)?))
+    }
+
+    /// Обмены, которые ещё не были воспроизведены
+    pub fn unused(&self) -> Vec<Interaction> {
+        let state = self.state.lock().unwrap();
+        state
+            .interactions
+            .iter()
+            .zip(&state.used)
+            .filter(|(_, used)| !**used)
+            .map(|(interaction, _)| interaction.clone())
+            .collect()
+    }
+
+    fn replay(&self, request: &YandexPayApiRequest) -> R<RawResponse> {
+        let request = RecordedRequest::new(request);
+        let mut state = self.state.lock().unwrap();
+        let ReplayState { interactions, used } = &mut *state;
+        let Some(index) = interactions
+            .iter()
+            .zip(used.iter())
+            .position(|(interaction, used)| !used && interaction.request.matches(&request))
+        else {
+            tracing::error!("Cassette has no interaction for {:?}", request);
+            return Err(YandexPayApiError::transport(format!(
+                "cassette has no unused interaction for {:?} {} with body {}",
+                request.method,
+                request.path(),
+                normalize(&request.body)
+            )));
+        };
+        used[index] = true;
+        interactions[index].response.clone().into_raw()
+    }
+}
+
+impl HttpClient for ReplayClient {
+    async fn send<T: serde::de::DeserializeOwned>(&self, request: YandexPayApiRequest) -> R<T> {
+        self.replay(&request)?.decode()
+    }
+}
+
+impl RawHttpClient for ReplayClient {
+    fn send_raw(&self, request: YandexPayApiRequest) -> RawFuture<'_> {
+        let response = self.replay(&request);
+        Box::pin(async move { response })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_order(order_id: &str) -> CreateOrderRequest {
+        let cart = RenderedCart::new()
+            .items(vec![
+                RenderedCartItem::new()
+                    .product_id("p1")
+                    .quantity(ItemQuantity::new().count(1.0).available(1.0).build())
+                    .title("Товар")
+                    .total(Money::from_rubles(100))
+                    .build(),
+            ])
+            .total(CartTotal::new().amount(Money::from_rubles(100)).build())
+            .external_id("cart")
+            .build();
+        CreateOrderRequest::new()
+            .cart(cart)
+            .order_id(order_id)
+            .build()
+    }
+
+    #[tokio::test]
+    async fn test_record_and_replay() {
+        let path = std::env::temp_dir().join(format!("cassette-{}.json", uuid::Uuid::now_v7()));
+        let recording = RecordingClient::new(FakeYandexPay::new(), &path);
+        let api = YandexPayApi::new(
+            "https://sandbox.pay.yandex.ru".into(),
+            "secret-key".into(),
+            recording,
+        );
+        api.create_order(create_order("order-1")).await.unwrap();
+        let recorded = api.get_order("order-1").await.unwrap();
+        let missing = api.get_order("missing").await.unwrap_err();
+
+        let replay = ReplayClient::from_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let api = YandexPayApi::new("http://localhost".into(), "secret-key".into(), replay);
+        api.create_order(create_order("order-1")).await.unwrap();
+        let replayed = api.get_order("order-1").await.unwrap();
+        assert_eq!(
+            serde_json::to_value(replayed).unwrap(),
+            serde_json::to_value(recorded).unwrap()
+        );
+        let err = api.get_order("missing").await.unwrap_err();
+        assert_eq!(err.http_status(), missing.http_status());
+        assert_eq!(err.reason_code(), Some(&ReasonCode::OrderNotFound));
+        assert!(api.client.unused().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_cassette_has_no_api_key() {
+        let path = std::env::temp_dir().join(format!("cassette-{}.json", uuid::Uuid::now_v7()));
+        let recording = RecordingClient::new(FakeYandexPay::new(), &path);
+        let api = YandexPayApi::new(
+            "https://sandbox.pay.yandex.ru".into(),
+            "secret-key".into(),
+            recording,
+        );
+        api.create_order(create_order("order-1")).await.unwrap();
+        api.get_order("order-1").await.unwrap();
+
+        let file = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        for forbidden in ["secret-key", "Api-Key", "Authorization"] {
+            assert!(!file.contains(forbidden), "{forbidden} in {file}");
+        }
+        assert!(file.contains("/api/merchant/v1/orders/order-1"));
+    }
+
+    #[tokio::test]
+    async fn test_replay_subscription_cancel_and_charges() {
+        let path = std::env::temp_dir().join(format!("cassette-{}.json", uuid::Uuid::now_v7()));
+        let fake = FakeYandexPay::new();
+        let api = YandexPayApi::new(
+            "https://sandbox.pay.yandex.ru".into(),
+            "secret-key".into(),
+            RecordingClient::new(fake.clone(), &path),
+        );
+        let created = api
+            .create_subscription(
+                CreateSubscriptionRequest::new()
+                    .order_id("start")
+                    .future_write_off_amount(Some(Money::from_rubles(100)))
+                    .build(),
+            )
+            .await
+            .unwrap();
+        let id = created.subscription_id;
+        let charges = ListSubscriptionChargesRequest::new().limit(Some(5)).build();
+        let recorded = api
+            .list_subscription_charges(&id, charges.clone())
+            .await
+            .unwrap();
+        let cancelled = api.cancel_subscription(&id).await.unwrap();
+
+        let file = std::fs::read_to_string(&path).unwrap();
+        assert!(file.contains(&format!("/subscriptions/{id}/charges?limit=5")));
+
+        let replay = ReplayClient::from_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let api = YandexPayApi::new("http://localhost".into(), "secret-key".into(), replay);
+        api.create_subscription(
+            CreateSubscriptionRequest::new()
+                .order_id("start")
+                .future_write_off_amount(Some(Money::from_rubles(100)))
+                .build(),
+        )
+        .await
+        .unwrap();
+        assert!(
+            api.list_subscription_charges(&id, ListSubscriptionChargesRequest::new().build())
+                .await
+                .is_err()
+        );
+        let replayed = api.list_subscription_charges(&id, charges).await.unwrap();
+        assert_eq!(replayed.parent_order_id, recorded.parent_order_id);
+        assert!(replayed.charges.is_empty());
+        let replayed = api.cancel_subscription(&id).await.unwrap();
+        assert_eq!(replayed.status, SubscriptionStatus::Cancelled);
+        assert_eq!(replayed.cancelled_at, cancelled.cancelled_at);
+        assert!(api.client.unused().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_replay_fails_on_unmatched_request() {
+        let cassette: Cassette = serde_json::from_value(serde_json::json!({
+            "interactions": [{
+                "request": {
+                    "method": "POST",
+                    "url": "https://sandbox.pay.yandex.ru/api/merchant/v1/orders/order-1/cancel",
+                    "body": {"reason": "test", "externalOperationId": null}
+                },
+                "response": {"status": 500, "body": "Internal Server Error"}
+            }]
+        }))
+        .unwrap();
+        let api = YandexPayApi::new(
+            "http://localhost".into(),
+            "key".into(),
+            ReplayClient::new(cassette),
+        );
+
+        let err = api
+            .cancel_order("order-1", CancelOrderRequest::new().reason("other").build())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, YandexPayApiError::Transport(_)), "{err:?}");
+        assert!(
+            err.to_string()
+                .contains("/api/merchant/v1/orders/order-1/cancel")
+        );
+
+        let err = api
+            .cancel_order("order-1", CancelOrderRequest::new().reason("test").build())
+            .await
+            .unwrap_err();
+        assert_eq!(err.http_status(), Some(500));
+        assert!(api.client.unused().is_empty());
+    }
+}
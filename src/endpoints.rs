@@ -0,0 +1,264 @@
+use serde::Serialize;
+
+use crate::*;
+
+/// Формирует запросы к API. Общий код для [`YandexPayApi`] и блокирующего клиента:
+/// клиенты отличаются только способом отправки.
+pub(crate) struct Endpoints<'a> {
+    pub base_url: &'a str,
+    pub api_key: &'a SecretKey,
+    pub validate_requests: bool,
+}
+
+/// Поля запроса в виде параметров URL. Поля со значением `null` пропускаются.
+fn query<Q: Serialize>(request: &Q) -> R<Vec<(String, String)>> {
+    let serde_json::Value::Object(fields) = serde_json::to_value(request)? else {
+        return Ok(Vec::new());
+    };
+    Ok(fields
+        .into_iter()
+        .filter(|(_, value)| !value.is_null())
+        .map(|(name, value)| match value {
+            serde_json::Value::String(value) => (name, value),
+            value => (name, value.to_string()),
+        })
+        .collect())
+}
+
+impl Endpoints<'_> {
+    fn validate<V: Validate>(&self, request: &V) -> R {
+        if self.validate_requests {
+            request.validate()?;
+        }
+        Ok(())
+    }
+
+    fn get(&self, path: String) -> YandexPayApiRequest {
+        YandexPayApiRequest::new()
+            .url(format!("{}{}", self.base_url, path))
+            .api_key(self.api_key.clone())
+            .method(Method::Get)
+            .build()
+    }
+
+    fn post<B: Serialize>(
+        &self,
+        path: String,
+        body: Option<&B>,
+        idempotent: bool,
+    ) -> R<YandexPayApiRequest> {
+        let body = match body {
+            Some(body) => Some(serde_json::to_vec(body)?.into()),
+            None => None,
+        };
+        Ok(YandexPayApiRequest::new()
+            .url(format!("{}{}", self.base_url, path))
+            .api_key(self.api_key.clone())
+            .method(Method::Post)
+            .body(body)
+            .idempotent(idempotent)
+            .build())
+    }
+
+    pub fn create_order(&self, request: &CreateOrderRequest) -> R<YandexPayApiRequest> {
+        self.validate(request)?;
+        self.post("/api/merchant/v1/orders".into(), Some(request), true)
+    }
+
+    pub fn get_order(&self, order_id: &str) -> R<YandexPayApiRequest> {
+        Ok(self.get(format!("/api/merchant/v1/orders/{order_id}")))
+    }
+
+    pub fn cancel_order(
+        &self,
+        order_id: &str,
+        request: &CancelOrderRequest,
+    ) -> R<YandexPayApiRequest> {
+        self.post(
+            format!("/api/merchant/v1/orders/{order_id}/cancel"),
+            Some(request),
+            request.external_operation_id.is_some(),
+        )
+    }
+
+    pub fn refund_order(&self, order_id: &str, request: &RefundRequest) -> R<YandexPayApiRequest> {
+        self.post(
+            format!("/api/merchant/v2/orders/{order_id}/refund"),
+            Some(request),
+            request.external_operation_id.is_some(),
+        )
+    }
+
+    pub fn capture_order(
+        &self,
+        order_id: &str,
+        request: &CaptureOrderRequest,
+    ) -> R<YandexPayApiRequest> {
+        self.post(
+            format!("/api/merchant/v1/orders/{order_id}/capture"),
+            Some(request),
+            request.external_operation_id.is_some(),
+        )
+    }
+
+    pub fn rollback_order(&self, order_id: &str) -> R<YandexPayApiRequest> {
+        self.post(
+            format!("/api/merchant/v1/orders/{order_id}/rollback"),
+            None::<&()>,
+            true,
+        )
+    }
+
+    pub fn submit_order(&self, order_id: &str, request: &SubmitRequest) -> R<YandexPayApiRequest> {
+        self.post(
+            format!("/api/merchant/v1/orders/{order_id}/submit"),
+            Some(request),
+            true,
+        )
+    }
+
+    pub fn get_operation(&self, external_operation_id: &str) -> R<YandexPayApiRequest> {
+        Ok(self.get(format!(
+            "/api/merchant/v1/operations/{external_operation_id}"
+        )))
+    }
+
+    pub fn create_subscription(
+        &self,
+        subscription: &CreateSubscriptionRequest,
+    ) -> R<YandexPayApiRequest> {
+        self.validate(subscription)?;
+        self.post(
+            "/api/merchant/v1/subscriptions".into(),
+            Some(subscription),
+            true,
+        )
+    }
+
+    pub fn recur_subscription(
+        &self,
+        subscription: &CreateRecurrentChargeRequest,
+    ) -> R<YandexPayApiRequest> {
+        self.validate(subscription)?;
+        self.post(
+            "/api/merchant/v1/subscriptions/recur".into(),
+            Some(subscription),
+            true,
+        )
+    }
+
+    pub fn get_subscription(
+        &self,
+        customer_subscription_id: &str,
+        request: &GetSubscriptionRequest,
+    ) -> R<YandexPayApiRequest> {
+        let mut r = self.get(format!(
+            "/api/merchant/v1/subscriptions/{customer_subscription_id}"
+        ));
+        r.query = query(request)?;
+        Ok(r)
+    }
+
+    pub fn cancel_subscription(&self, customer_subscription_id: &str) -> R<YandexPayApiRequest> {
+        self.post(
+            format!("/api/merchant/v1/subscriptions/{customer_subscription_id}/cancel"),
+            None::<&()>,
+            true,
+        )
+    }
+
+    pub fn list_subscription_charges(
+        &self,
+        customer_subscription_id: &str,
+        request: &ListSubscriptionChargesRequest,
+    ) -> R<YandexPayApiRequest> {
+        let mut r = self.get(format!(
+            "/api/merchant/v1/subscriptions/{customer_subscription_id}/charges"
+        ));
+        r.query = query(request)?;
+        Ok(r)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn endpoints(api_key: &SecretKey) -> Endpoints<'_> {
+        Endpoints {
+            base_url: "https://sandbox.pay.yandex.ru",
+            api_key,
+            validate_requests: false,
+        }
+    }
+
+    #[test]
+    fn test_get_subscription_uses_query() {
+        let api_key = SecretKey::new("key");
+        let r = endpoints(&api_key)
+            .get_subscription(
+                "sub-1",
+                &GetSubscriptionRequest::new()
+                    .check_card_active(true)
+                    .build(),
+            )
+            .unwrap();
+        assert_eq!(r.method, Method::Get);
+        assert!(r.body.is_none());
+        assert_eq!(
+            r.full_url(),
+            "https://sandbox.pay.yandex.ru/api/merchant/v1/subscriptions/sub-1?checkCardActive=true"
+        );
+    }
+
+    #[test]
+    fn test_subscription_cancel_and_charges_urls() {
+        let api_key = SecretKey::new("key");
+        let r = endpoints(&api_key).cancel_subscription("sub-1").unwrap();
+        assert_eq!(r.method, Method::Post);
+        assert!(r.is_idempotent());
+        assert_eq!(
+            r.full_url(),
+            "https://sandbox.pay.yandex.ru/api/merchant/v1/subscriptions/sub-1/cancel"
+        );
+
+        let r = endpoints(&api_key)
+            .list_subscription_charges("sub-1", &ListSubscriptionChargesRequest::new().build())
+            .unwrap();
+        assert_eq!(
+            r.full_url(),
+            "https://sandbox.pay.yandex.ru/api/merchant/v1/subscriptions/sub-1/charges"
+        );
+        let r = endpoints(&api_key)
+            .list_subscription_charges(
+                "sub-1",
+                &ListSubscriptionChargesRequest::new()
+                    .limit(Some(10))
+                    .offset(Some(20))
+                    .build(),
+            )
+            .unwrap();
+        assert_eq!(
+            r.full_url(),
+            "https://sandbox.pay.yandex.ru/api/merchant/v1/subscriptions/sub-1/charges?limit=10&offset=20"
+        );
+    }
+
+    #[test]
+    fn test_query_encoding() {
+        let api_key = SecretKey::new("key");
+        let mut r = endpoints(&api_key).get_order("order-1").unwrap();
+        assert_eq!(
+            r.full_url(),
+            "https://sandbox.pay.yandex.ru/api/merchant/v1/orders/order-1"
+        );
+        r.query = vec![
+            ("from".into(), "2025-01-01T00:00:00+03:00".into()),
+            ("q".into(), "a b&c".into()),
+        ];
+        assert_eq!(
+            r.full_url(),
+            "https://sandbox.pay.yandex.ru/api/merchant/v1/orders/order-1?from=2025-01-01T00%3A00%3A00%2B03%3A00&q=a+b%26c"
+        );
+    }
+}
@@ -0,0 +1,220 @@
+use std::str::FromStr;
+
+use crate::*;
+
+pub const SANDBOX_BASE_URL: &str = "https://sandbox.pay.yandex.ru";
+pub const PRODUCTION_BASE_URL: &str = "https://pay.yandex.ru";
+
+/// Окружение Yandex Pay API
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum YandexPayEnvironment {
+    Sandbox,
+    Production,
+    /// Произвольный адрес, например локальный фейковый сервер
+    Custom(S),
+}
+
+impl YandexPayEnvironment {
+    pub fn base_url(&self) -> &str {
+        match self {
+            YandexPayEnvironment::Sandbox => SANDBOX_BASE_URL,
+            YandexPayEnvironment::Production => PRODUCTION_BASE_URL,
+            YandexPayEnvironment::Custom(url) => url,
+        }
+    }
+
+    /// Отклоняет ключ песочницы в продакшене.
+    ///
+    /// В песочнице ключ API совпадает с `merchant_id` и имеет формат UUID; в продакшене
+    /// такой ключ почти наверняка означает перепутанные настройки.
+    pub fn check_api_key(
+        &self,
+        api_key: &str,
+        merchant_id: Option<&str>,
+    ) -> R<(), EnvironmentError> {
+        if *self != YandexPayEnvironment::Production {
+            return Ok(());
+        }
+        if api_key.is_empty() {
+            return Err(EnvironmentError::MissingVar("YANDEX_PAY_API_KEY"));
+        }
+        if uuid::Uuid::parse_str(api_key).is_ok() || merchant_id == Some(api_key) {
+            return Err(EnvironmentError::SandboxKeyInProduction);
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for YandexPayEnvironment {
+    type Err = EnvironmentError;
+
+    /// `sandbox`, `production` (`prod`) или URL, начинающийся с `http://` / `https://`
+    fn from_str(value: &str) -> R<Self, EnvironmentError> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "sandbox" => Ok(YandexPayEnvironment::Sandbox),
+            "production" | "prod" => Ok(YandexPayEnvironment::Production),
+            url if url.starts_with("http://") || url.starts_with("https://") => Ok(
+                YandexPayEnvironment::Custom(value.trim().trim_end_matches('/').into()),
+            ),
+            _ => Err(EnvironmentError::InvalidEnvironment(value.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum EnvironmentError {
+    #[error("environment variable {0} is not set")]
+    MissingVar(&'static str),
+    #[error("unknown Yandex Pay environment {0:?}, expected sandbox, production or a URL")]
+    InvalidEnvironment(String),
+    #[error("refusing to use a sandbox API key (merchant id) in production")]
+    SandboxKeyInProduction,
+}
+
+impl<C: HttpClient> YandexPayApi<C> {
+    /// Создаёт клиент для выбранного окружения.
+    ///
+    /// Возвращает [`EnvironmentError::SandboxKeyInProduction`], если для продакшена передан
+    /// ключ песочницы.
+    pub fn with_environment(
+        environment: YandexPayEnvironment,
+        api_key: SecretKey,
+        client: C,
+    ) -> R<Self> {
+        environment.check_api_key(api_key.expose(), None)?;
+        Ok(YandexPayApi::new(
+            environment.base_url().into(),
+            api_key,
+            client,
+        ))
+    }
+
+    /// Создаёт клиент по переменным окружения.
+    ///
+    /// - `YANDEX_PAY_ENV` — `sandbox` (по умолчанию), `production` или URL;
+    /// - `YANDEX_PAY_API_KEY` — ключ API. В песочнице, если не задан, используется
+    ///   `YANDEX_MERCHANT_ID`: там ключ совпадает с идентификатором продавца;
+    /// - `YANDEX_MERCHANT_ID` — идентификатор продавца.
+    pub fn from_env(client: C) -> R<Self> {
+        Self::from_vars(|name| std::env::var(name).ok(), client)
+    }
+
+    pub(crate) fn from_vars(var: impl Fn(&str) -> Option<String>, client: C) -> R<Self> {
+        let environment = match var("YANDEX_PAY_ENV") {
+            Some(environment) => environment.parse()?,
+            None => YandexPayEnvironment::Sandbox,
+        };
+        let merchant_id = var("YANDEX_MERCHANT_ID");
+        let api_key = match (var("YANDEX_PAY_API_KEY"), &environment) {
+            (Some(api_key), _) => api_key,
+            (None, YandexPayEnvironment::Production) => {
+                return Err(EnvironmentError::MissingVar("YANDEX_PAY_API_KEY").into());
+            }
+            (None, _) => merchant_id
+                .clone()
+                .ok_or(EnvironmentError::MissingVar("YANDEX_MERCHANT_ID"))?,
+        };
+        environment.check_api_key(&api_key, merchant_id.as_deref())?;
+        Ok(YandexPayApi::new(
+            environment.base_url().into(),
+            api_key.into(),
+            client,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::test_client::NoClient;
+
+    const MERCHANT_ID: &str = "0199a1d2-5f3e-7c1a-9b2e-0123456789ab";
+
+    fn from_vars(vars: &[(&str, &str)]) -> R<YandexPayApi<NoClient>> {
+        let vars: HashMap<_, _> = vars.iter().copied().collect();
+        YandexPayApi::from_vars(
+            |name| vars.get(name).map(|value| value.to_string()),
+            NoClient,
+        )
+    }
+
+    #[test]
+    fn test_parse_environment() {
+        assert_eq!("Sandbox".parse(), Ok(YandexPayEnvironment::Sandbox));
+        assert_eq!("prod".parse(), Ok(YandexPayEnvironment::Production));
+        assert_eq!(
+            "http://localhost:8080/".parse(),
+            Ok(YandexPayEnvironment::Custom("http://localhost:8080".into()))
+        );
+        assert_eq!(
+            "staging".parse::<YandexPayEnvironment>(),
+            Err(EnvironmentError::InvalidEnvironment("staging".into()))
+        );
+    }
+
+    #[test]
+    fn test_from_env() {
+        let api = from_vars(&[("YANDEX_MERCHANT_ID", MERCHANT_ID)]).unwrap();
+        assert_eq!(api.get_base_url(), SANDBOX_BASE_URL);
+        assert_eq!(api.get_api_key().expose(), MERCHANT_ID);
+
+        let api = from_vars(&[
+            ("YANDEX_PAY_ENV", "production"),
+            ("YANDEX_MERCHANT_ID", MERCHANT_ID),
+            ("YANDEX_PAY_API_KEY", "production-key"),
+        ])
+        .unwrap();
+        assert_eq!(api.get_base_url(), PRODUCTION_BASE_URL);
+        assert_eq!(api.get_api_key().expose(), "production-key");
+
+        assert!(matches!(
+            from_vars(&[
+                ("YANDEX_PAY_ENV", "production"),
+                ("YANDEX_MERCHANT_ID", MERCHANT_ID)
+            ]),
+            Err(YandexPayApiError::Environment(
+                EnvironmentError::MissingVar("YANDEX_PAY_API_KEY")
+            ))
+        ));
+        assert!(matches!(
+            from_vars(&[]),
+            Err(YandexPayApiError::Environment(
+                EnvironmentError::MissingVar("YANDEX_MERCHANT_ID")
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_refuses_sandbox_key_in_production() {
+        assert!(matches!(
+            from_vars(&[
+                ("YANDEX_PAY_ENV", "production"),
+                ("YANDEX_PAY_API_KEY", "merchant"),
+                ("YANDEX_MERCHANT_ID", "merchant"),
+            ]),
+            Err(YandexPayApiError::Environment(
+                EnvironmentError::SandboxKeyInProduction
+            ))
+        ));
+        assert!(matches!(
+            YandexPayApi::with_environment(
+                YandexPayEnvironment::Production,
+                MERCHANT_ID.into(),
+                NoClient
+            ),
+            Err(YandexPayApiError::Environment(
+                EnvironmentError::SandboxKeyInProduction
+            ))
+        ));
+        assert!(
+            YandexPayApi::with_environment(
+                YandexPayEnvironment::Sandbox,
+                MERCHANT_ID.into(),
+                NoClient
+            )
+            .is_ok()
+        );
+    }
+}
@@ -0,0 +1,53 @@
+//! Преобразование запросов к API в типы крейта `http`.
+
+use bytes::Bytes;
+use http_body_util::BodyExt;
+
+use crate::*;
+
+impl YandexPayApiRequest {
+    /// Преобразует запрос в [`http::Request`] с заголовками из [`YandexPayApiRequest::headers`].
+    ///
+    /// Параметры запроса добавляются к URI, пустое тело заменяется на [`Bytes::new`].
+    pub fn into_http_request(self) -> R<http::Request<Bytes>> {
+        let method = match self.method {
+            Method::Get => http::Method::GET,
+            Method::Post => http::Method::POST,
+        };
+        let mut builder = http::Request::builder().method(method).uri(self.full_url());
+        for (name, value) in self.headers() {
+            let mut value =
+                http::HeaderValue::try_from(value).map_err(YandexPayApiError::transport)?;
+            // Маскирует ключ API в `Debug` заголовков
+            value.set_sensitive(name == "Authorization");
+            builder = builder.header(name, value);
+        }
+        builder
+            .body(self.body.unwrap_or_default())
+            .map_err(YandexPayApiError::transport)
+    }
+}
+
+impl TryFrom<YandexPayApiRequest> for http::Request<Bytes> {
+    type Error = YandexPayApiError;
+
+    fn try_from(request: YandexPayApiRequest) -> R<Self> {
+        request.into_http_request()
+    }
+}
+
+/// Читает тело ответа целиком
+pub(crate) async fn read_http_response<B>(response: http::Response<B>) -> R<RawResponse>
+where
+    B: http_body::Body,
+    B::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    let status = response.status().as_u16();
+    let body = response
+        .into_body()
+        .collect()
+        .await
+        .map_err(YandexPayApiError::transport)?
+        .to_bytes();
+    Ok(RawResponse { status, body })
+}
@@ -0,0 +1,125 @@
+//! Реализация [`HttpClient`] на основе `hyper-util` без зависимости от reqwest.
+
+use std::time::Duration;
+
+use bytes::Bytes;
+use http_body_util::Full;
+use hyper_util::client::legacy::Client;
+use hyper_util::client::legacy::connect::Connect;
+
+use crate::http_request::read_http_response;
+use crate::*;
+
+/// Клиент `hyper-util` с телом запроса [`Full<Bytes>`].
+///
+/// Коннектор выбирает вызывающая сторона: для HTTPS подойдёт, например,
+/// `hyper_rustls::HttpsConnector`.
+///
+/// ```no_run
+/// # use yandex_pay_api::*;
+/// use hyper_util::client::legacy::Client;
+/// use hyper_util::client::legacy::connect::HttpConnector;
+/// use hyper_util::rt::TokioExecutor;
+///
+/// # async fn run() -> Result<(), YandexPayApiError> {
+/// let client: HyperClient<HttpConnector> = Client::builder(TokioExecutor::new()).build_http();
+/// let api = YandexPayApi::new("http://localhost:8080".into(), "merchant-id".into(), client);
+/// let order = api.get_order("order-1").await?;
+/// # Ok(())
+/// # }
+/// ```
+pub type HyperClient<Conn> = Client<Conn, Full<Bytes>>;
+
+impl<Conn> HttpClient for HyperClient<Conn>
+where
+    Conn: Connect + Clone + Send + Sync + 'static,
+{
+    async fn send<T: serde::de::DeserializeOwned>(&self, request: YandexPayApiRequest) -> R<T> {
+        self.send_raw(request).await?.decode()
+    }
+}
+
+impl<Conn> RawHttpClient for HyperClient<Conn>
+where
+    Conn: Connect + Clone + Send + Sync + 'static,
+{
+    fn send_raw(&self, request: YandexPayApiRequest) -> RawFuture<'_> {
+        Box::pin(async move {
+            let timeout = Duration::from_millis(request.request_timeout.into());
+            let request = request.into_http_request()?.map(Full::new);
+            let send = async {
+                let response = self
+                    .request(request)
+                    .await
+                    .map_err(YandexPayApiError::transport)?;
+                read_http_response(response).await
+            };
+            tokio::time::timeout(timeout, send)
+                .await
+                .map_err(|_| YandexPayApiError::Timeout)?
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hyper_util::client::legacy::connect::HttpConnector;
+    use hyper_util::rt::TokioExecutor;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    use super::*;
+
+    fn api(base_url: String) -> YandexPayApi<HyperClient<HttpConnector>> {
+        let client = Client::builder(TokioExecutor::new()).build_http();
+        YandexPayApi::new(base_url.into(), "key".into(), client)
+    }
+
+    #[tokio::test]
+    async fn test_hyper_get_operation() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut head = vec![0; 4096];
+            let len = socket.read(&mut head).await.unwrap();
+            let body = r#"{"status": "success", "data": {"operation": {"amount": "10.00", "operationId": "op", "operationType": "REFUND", "orderId": "order-1", "status": "SUCCESS"}}}"#;
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{body}",
+                body.len()
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+            String::from_utf8_lossy(&head[..len]).into_owned()
+        });
+
+        let response = api(base_url).get_operation("ext-1").await.unwrap();
+        assert_eq!(response.operation.order_id, "order-1");
+        let request = server.await.unwrap();
+        assert!(request.starts_with("GET /api/merchant/v1/operations/ext-1 "));
+        assert!(request.contains("authorization: Api-Key key\r\n"));
+    }
+
+    #[tokio::test]
+    async fn test_hyper_timeout_and_transport_error() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (_socket, _) = listener.accept().await.unwrap();
+            tokio::time::sleep(Duration::from_secs(5)).await;
+        });
+        let result = api(base_url)
+            .with_request_timeout(Duration::from_millis(100))
+            .get_order("order")
+            .await;
+        assert!(
+            matches!(result, Err(YandexPayApiError::Timeout)),
+            "{result:?}"
+        );
+        server.abort();
+
+        let result = api("http://127.0.0.1:1".into()).get_order("order").await;
+        assert!(
+            matches!(result, Err(YandexPayApiError::Transport(_))),
+            "{result:?}"
+        );
+    }
+}
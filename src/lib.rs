@@ -1,3 +1,22 @@
+#[cfg(feature = "actix")]
+mod actix_service;
+#[cfg(feature = "axum")]
+mod axum_router;
+#[cfg(feature = "blocking")]
+mod blocking;
+mod cart;
+#[cfg(feature = "testing")]
+mod cassette;
+mod endpoints;
+mod environment;
+#[cfg(any(feature = "hyper", feature = "tower"))]
+mod http_request;
+#[cfg(feature = "hyper")]
+mod hyper_client;
+#[cfg(feature = "merchant")]
+mod merchant;
+mod money;
+mod order_handle;
 mod orders;
 mod orders_cancel;
 mod orders_capture;
@@ -5,13 +24,48 @@ mod orders_id;
 mod orders_refund;
 mod orders_submit;
 mod orders_subscriptions;
+mod orders_subscriptions_charges;
 mod orders_subscriptions_id;
 mod orders_subscriptions_recur;
+mod raw_client;
+mod reason_code;
+mod refund_plan;
+mod retry;
+mod secret;
 mod serde_help;
+mod subscription_manager;
+#[cfg(test)]
+mod test_client;
+#[cfg(feature = "testing")]
+mod testing;
+#[cfg(feature = "tower")]
+mod tower_client;
+mod validate;
+mod wait;
+#[cfg(feature = "webhook")]
+mod webhook;
 use std::sync::Arc;
+use std::time::Duration;
 
+#[cfg(feature = "actix")]
+pub use actix_service::*;
+#[cfg(feature = "axum")]
+pub use axum_router::*;
+#[cfg(feature = "blocking")]
+pub use blocking::*;
 use builder_pattern::Builder;
 use bytes::Bytes;
+pub use cart::*;
+#[cfg(feature = "testing")]
+pub use cassette::*;
+use endpoints::Endpoints;
+pub use environment::*;
+#[cfg(feature = "hyper")]
+pub use hyper_client::*;
+#[cfg(feature = "merchant")]
+pub use merchant::*;
+pub use money::*;
+pub use order_handle::*;
 pub use orders::*;
 pub use orders_cancel::*;
 pub use orders_capture::*;
@@ -19,8 +73,23 @@ pub use orders_id::*;
 pub use orders_refund::*;
 pub use orders_submit::*;
 pub use orders_subscriptions::*;
+pub use orders_subscriptions_charges::*;
 pub use orders_subscriptions_id::*;
 pub use orders_subscriptions_recur::*;
+pub use raw_client::*;
+pub use reason_code::*;
+pub use refund_plan::*;
+pub use retry::*;
+pub use secret::*;
+pub use subscription_manager::*;
+#[cfg(feature = "testing")]
+pub use testing::*;
+#[cfg(feature = "tower")]
+pub use tower_client::*;
+pub use validate::*;
+pub use wait::*;
+#[cfg(feature = "webhook")]
+pub use webhook::*;
 
 pub trait HttpClient: Clone {
     fn send<T: serde::de::DeserializeOwned>(
@@ -35,12 +104,48 @@ pub(crate) type Time = chrono::DateTime<chrono::Utc>;
 #[derive(Debug, thiserror::Error)]
 #[error("Yandex Pay API error: {0}")]
 pub enum YandexPayApiError {
-    #[error("Yandex Pay reqwest error: {0}")]
-    Reqwest(#[from] reqwest::Error),
+    /// Ошибка HTTP-клиента: соединение, TLS, чтение ответа и т. п.
+    #[error("Yandex Pay transport error: {0}")]
+    Transport(Box<dyn std::error::Error + Send + Sync>),
     #[error("Yandex Pay serde error: {0}")]
     Serde(#[from] serde_json::Error),
     #[error("Yandex Pay API error: {0}")]
     Api(YandexPayApiResponseError),
+    #[error("Yandex Pay request timed out")]
+    Timeout,
+    #[error("Yandex Pay operation {external_operation_id} did not finish in time")]
+    OperationTimeout {
+        external_operation_id: String,
+        /// Последнее полученное состояние операции
+        last: Option<Box<Operation>>,
+    },
+    #[error("Yandex Pay validation error: {0}")]
+    Validation(#[from] ValidationError),
+    #[error("Yandex Pay environment error: {0}")]
+    Environment(#[from] EnvironmentError),
+    #[error("Yandex Pay subscription error: {0}")]
+    Subscription(#[from] SubscriptionError),
+    #[cfg(feature = "webhook")]
+    #[error("Yandex Pay webhook error: {0}")]
+    Webhook(#[from] WebhookError),
+}
+
+impl YandexPayApiError {
+    /// Оборачивает ошибку HTTP-клиента. Для собственных реализаций [`HttpClient`].
+    pub fn transport(err: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> Self {
+        YandexPayApiError::Transport(err.into())
+    }
+}
+
+#[cfg(feature = "reqwest")]
+impl From<reqwest::Error> for YandexPayApiError {
+    fn from(err: reqwest::Error) -> Self {
+        if err.is_timeout() {
+            YandexPayApiError::Timeout
+        } else {
+            YandexPayApiError::transport(err)
+        }
+    }
 }
 
 pub(crate) type S = Arc<str>;
@@ -49,7 +154,12 @@ pub(crate) type S = Arc<str>;
 pub struct YandexPayApi<C: HttpClient> {
     pub client: C,
     pub base_url: S,
-    pub api_key: S,
+    pub api_key: SecretKey,
+    pub retry_policy: Option<RetryPolicy>,
+    /// Таймаут запроса, если не задан — используется значение по умолчанию `YandexPayApiRequest`
+    pub request_timeout: Option<Duration>,
+    /// Проверять ли запросы на создание заказов и подписок через [`Validate`] перед отправкой
+    pub validate_requests: bool,
 }
 
 #[cfg(feature = "reqwest")]
@@ -57,14 +167,49 @@ pub struct YandexPayApi<C: HttpClient> {
 pub struct YandexPayApi<C: HttpClient = reqwest::Client> {
     pub client: C,
     pub base_url: S,
-    pub api_key: S,
+    pub api_key: SecretKey,
+    pub retry_policy: Option<RetryPolicy>,
+    /// Таймаут запроса, если не задан — используется значение по умолчанию `YandexPayApiRequest`
+    pub request_timeout: Option<Duration>,
+    /// Проверять ли запросы на создание заказов и подписок через [`Validate`] перед отправкой
+    pub validate_requests: bool,
 }
 impl<C: HttpClient> YandexPayApi<C> {
-    pub fn new(base_url: S, api_key: S, client: C) -> Self {
+    pub fn new(base_url: S, api_key: SecretKey, client: C) -> Self {
         YandexPayApi {
             client,
             base_url,
             api_key,
+            retry_policy: None,
+            request_timeout: None,
+            validate_requests: false,
+        }
+    }
+
+    /// Включает локальную проверку запросов перед отправкой.
+    ///
+    /// При нарушениях возвращается [`YandexPayApiError::Validation`] со всеми найденными ошибками.
+    pub fn with_validation(mut self, validate_requests: bool) -> Self {
+        self.validate_requests = validate_requests;
+        self
+    }
+
+    pub(crate) fn endpoints(&self) -> Endpoints<'_> {
+        Endpoints {
+            base_url: &self.base_url,
+            api_key: &self.api_key,
+            validate_requests: self.validate_requests,
+        }
+    }
+
+    /// Возвращает копию клиента с другим таймаутом запросов.
+    ///
+    /// Удобно для переопределения таймаута на отдельный вызов:
+    /// `api.with_request_timeout(Duration::from_secs(30)).refund_order(..)`.
+    pub fn with_request_timeout(&self, request_timeout: Duration) -> Self {
+        YandexPayApi {
+            request_timeout: Some(request_timeout),
+            ..self.clone()
         }
     }
 
@@ -72,7 +217,7 @@ impl<C: HttpClient> YandexPayApi<C> {
         &self.base_url
     }
 
-    pub fn get_api_key(&self) -> &str {
+    pub fn get_api_key(&self) -> &SecretKey {
         &self.api_key
     }
 }
@@ -83,31 +228,16 @@ impl<C: HttpClient> YandexPayApi<C> {
     ///
     /// Запрос используется для создания и получения ссылки на оплату заказа.
     pub async fn create_order(&self, request: CreateOrderRequest) -> R<CreateOrderResponse> {
-        let url = format!("{}/api/merchant/v1/orders", self.base_url);
-        let bytes = serde_json::to_vec(&request)?;
-        let r = YandexPayApiRequest::new()
-            .url(url)
-            .method(Method::Post)
-            .body(Some(bytes.into()))
-            .api_key(self.api_key.clone())
-            .build();
-        let response = self.client.send(r).await?;
+        let r = self.endpoints().create_order(&request)?;
+        let response = self.send(r).await?;
         Ok(response)
     }
     /// Запрос на получение деталей заказа.
     ///
     /// Запрос возвращает детали заказа и список транзакций по возврату.
     pub async fn get_order(&self, order_id: impl Into<String>) -> R<OrderResponseData> {
-        let url = format!(
-            "{}/api/merchant/v1/orders/{}",
-            self.base_url,
-            order_id.into()
-        );
-        let r = YandexPayApiRequest::new()
-            .url(url)
-            .api_key(self.api_key.clone())
-            .build();
-        let response = self.client.send(r).await?;
+        let r = self.endpoints().get_order(&order_id.into())?;
+        let response = self.send(r).await?;
         Ok(response)
     }
     /// Запрос на отмену платежа.
@@ -118,28 +248,17 @@ impl<C: HttpClient> YandexPayApi<C> {
         order_id: impl Into<String>,
         request: CancelOrderRequest,
     ) -> R<OperationResponseData> {
-        let url = format!(
-            "{}/api/merchant/v1/orders/{}/cancel",
-            self.base_url,
-            order_id.into()
-        );
-        let bytes = serde_json::to_vec(&request)?;
-        let r = YandexPayApiRequest::new()
-            .url(url)
-            .api_key(self.api_key.clone())
-            .method(Method::Post)
-            .body(Some(bytes.into()))
-            .build();
-        let response = self.client.send(r).await?;
+        let r = self.endpoints().cancel_order(&order_id.into(), &request)?;
+        let response = self.send(r).await?;
         Ok(response)
     }
     /// Запрос на возврат средств за заказ.
     ///
     /// Доступно только для платежей в статусе CAPTURED и PARTIALLY_REFUNDED. В случае успешного выполнения запроса изменится статус платежа:
     ///
-    ///     на REFUNDED, если был произведен полный возврат;
+    /// на REFUNDED, если был произведен полный возврат;
     ///
-    ///     на PARTIALLY_REFUNDED, если после совершения возврата в заказе остались ещё товары.
+    /// на PARTIALLY_REFUNDED, если после совершения возврата в заказе остались ещё товары.
     ///
     /// Метод является асинхронным.
     ///
@@ -148,33 +267,22 @@ impl<C: HttpClient> YandexPayApi<C> {
     /// Для выполнения полного возврата достаточно передать refundAmount, равный сумме заказа.
     ///
     /// Для выполнения частичного возврата дополнительно нужно передать итоговую корзину предоставляемых товаров и услуг. Сформировать итоговую корзину можно одним из способов:
-    ///     передать целевое состояние корзины после выполнения возврата с помощью поля targetCart. Если это поле не указано, то считается, что корзина возвращается полностью.
+    /// передать целевое состояние корзины после выполнения возврата с помощью поля targetCart. Если это поле не указано, то считается, что корзина возвращается полностью.
     ///
-    ///     Поле targetShipping применимо только к Yandex Pay Checkout. В остальных случаях следует оставить это поле пустым. Если это поле не указано, то считается, что стоимость доставки возвращается полностью.
-    ///     
-    ///     передать данные о товарах, подлежащих возврату, с помошью поля refundCart: в поле укажите, сколько единиц товара нужно вернуть или на какую сумму следует уменьшить стоимость товара. Если поле не указано, возврат осуществляется на всю корзину.
+    /// Поле targetShipping применимо только к Yandex Pay Checkout. В остальных случаях следует оставить это поле пустым. Если это поле не указано, то считается, что стоимость доставки возвращается полностью.
+    ///
+    /// передать данные о товарах, подлежащих возврату, с помошью поля refundCart: в поле укажите, сколько единиц товара нужно вернуть или на какую сумму следует уменьшить стоимость товара. Если поле не указано, возврат осуществляется на всю корзину.
     ///
     /// Примечание
     ///
-    ///     Для данной стратегии рекомендуется указывать идентификатор операции externalOperationId, который служит токеном идемпотентности. Это позволит избежать риска повторных возвратов.
+    /// Для данной стратегии рекомендуется указывать идентификатор операции externalOperationId, который служит токеном идемпотентности. Это позволит избежать риска повторных возвратов.
     pub async fn refund_order(
         &self,
         order_id: impl Into<String>,
         request: RefundRequest,
     ) -> R<OperationResponseData> {
-        let url = format!(
-            "{}/api/merchant/v2/orders/{}/refund",
-            self.base_url,
-            order_id.into()
-        );
-        let bytes = serde_json::to_vec(&request)?;
-        let r = YandexPayApiRequest::new()
-            .url(url)
-            .api_key(self.api_key.clone())
-            .method(Method::Post)
-            .body(Some(bytes.into()))
-            .build();
-        let response = self.client.send(r).await?;
+        let r = self.endpoints().refund_order(&order_id.into(), &request)?;
+        let response = self.send(r).await?;
         Ok(response)
     }
     /// Запрос на списание средств за заказ.
@@ -187,36 +295,16 @@ impl<C: HttpClient> YandexPayApi<C> {
         order_id: impl Into<String>,
         request: CaptureOrderRequest,
     ) -> R<OperationResponseData> {
-        let url = format!(
-            "{}/api/merchant/v1/orders/{}/capture",
-            self.base_url,
-            order_id.into()
-        );
-        let bytes = serde_json::to_vec(&request)?;
-        let r = YandexPayApiRequest::new()
-            .url(url)
-            .api_key(self.api_key.clone())
-            .method(Method::Post)
-            .body(Some(bytes.into()))
-            .build();
-        let response = self.client.send(r).await?;
+        let r = self.endpoints().capture_order(&order_id.into(), &request)?;
+        let response = self.send(r).await?;
         Ok(response)
     }
     /// Запрос на отмену платежа.
     ///
     /// Доступно для платежей в любом статусе. Запрещает дальнейшую оплату заказа, а также, если оплата уже произошла, производит полный возврат средств клиенту. В случае успеха статус платежа изменится на FAILED.
     pub async fn rollback_order(&self, order_id: impl Into<String>) -> R<serde_json::Value> {
-        let url = format!(
-            "{}/api/merchant/v1/orders/{}/rollback",
-            self.base_url,
-            order_id.into()
-        );
-        let r = YandexPayApiRequest::new()
-            .url(url)
-            .api_key(self.api_key.clone())
-            .method(Method::Post)
-            .build();
-        let response = self.client.send(r).await?;
+        let r = self.endpoints().rollback_order(&order_id.into())?;
+        let response = self.send(r).await?;
         Ok(response)
     }
 
@@ -230,19 +318,8 @@ impl<C: HttpClient> YandexPayApi<C> {
         order_id: impl Into<String>,
         request: SubmitRequest,
     ) -> R<OperationResponseData> {
-        let url = format!(
-            "{}/api/merchant/v1/orders/{}/submit",
-            self.base_url,
-            order_id.into()
-        );
-        let bytes = serde_json::to_vec(&request)?;
-        let r = YandexPayApiRequest::new()
-            .url(url)
-            .api_key(self.api_key.clone())
-            .method(Method::Post)
-            .body(Some(bytes.into()))
-            .build();
-        let response = self.client.send(r).await?;
+        let r = self.endpoints().submit_order(&order_id.into(), &request)?;
+        let response = self.send(r).await?;
         Ok(response)
     }
 
@@ -250,17 +327,10 @@ impl<C: HttpClient> YandexPayApi<C> {
         &self,
         external_operation_id: impl Into<String>,
     ) -> R<OperationResponseData> {
-        let url = format!(
-            "{}/api/merchant/v1/operations/{}",
-            self.base_url,
-            external_operation_id.into()
-        );
-        let r = YandexPayApiRequest::new()
-            .url(url)
-            .api_key(self.api_key.clone())
-            .method(Method::Get)
-            .build();
-        let response = self.client.send(r).await?;
+        let r = self
+            .endpoints()
+            .get_operation(&external_operation_id.into())?;
+        let response = self.send(r).await?;
         Ok(response)
     }
     /// Запрос на создание подписки.
@@ -270,15 +340,8 @@ impl<C: HttpClient> YandexPayApi<C> {
         &self,
         subscription: CreateSubscriptionRequest,
     ) -> Result<CreateSubscriptionResponseData, YandexPayApiError> {
-        let url = format!("{}/api/merchant/v1/subscriptions", self.base_url);
-        let bytes = serde_json::to_vec(&subscription)?;
-        let r = YandexPayApiRequest::new()
-            .url(url)
-            .api_key(self.api_key.clone())
-            .method(Method::Post)
-            .body(Some(bytes.into()))
-            .build();
-        let response = self.client.send(r).await?;
+        let r = self.endpoints().create_subscription(&subscription)?;
+        let response = self.send(r).await?;
         Ok(response)
     }
 
@@ -289,15 +352,8 @@ impl<C: HttpClient> YandexPayApi<C> {
         &self,
         subscription: CreateRecurrentChargeRequest,
     ) -> Result<RecurSubscriptionResponseData, YandexPayApiError> {
-        let url = format!("{}/api/merchant/v1/subscriptions/recur", self.base_url);
-        let bytes = serde_json::to_vec(&subscription)?;
-        let r = YandexPayApiRequest::new()
-            .url(url)
-            .api_key(self.api_key.clone())
-            .method(Method::Post)
-            .body(Some(bytes.into()))
-            .build();
-        let response = self.client.send(r).await?;
+        let r = self.endpoints().recur_subscription(&subscription)?;
+        let response = self.send(r).await?;
         Ok(response)
     }
 
@@ -310,24 +366,47 @@ impl<C: HttpClient> YandexPayApi<C> {
         customer_subscription_id: impl Into<String>,
         request: GetSubscriptionRequest,
     ) -> Result<CustomerSubscriptionResponseData, YandexPayApiError> {
-        let url = format!(
-            "{}/api/merchant/v1/subscriptions/{}",
-            self.base_url,
-            customer_subscription_id.into()
-        );
-        let bytes = serde_json::to_vec(&request)?;
-        let r = YandexPayApiRequest::new()
-            .url(url)
-            .api_key(self.api_key.clone())
-            .method(Method::Get)
-            .body(Some(bytes.into()))
-            .build();
-        let response = self.client.send(r).await?;
+        let r = self
+            .endpoints()
+            .get_subscription(&customer_subscription_id.into(), &request)?;
+        let response = self.send(r).await?;
+        Ok(response)
+    }
+
+    /// Запрос на отмену подписки.
+    ///
+    /// После отмены рекуррентные списания невозможны, в ответе заполнено `cancelled_at`.
+    /// Повторная отмена возвращает ту же подписку.
+    pub async fn cancel_subscription(
+        &self,
+        customer_subscription_id: impl Into<String>,
+    ) -> R<CustomerSubscriptionResponseData> {
+        let r = self
+            .endpoints()
+            .cancel_subscription(&customer_subscription_id.into())?;
+        let response = self.send(r).await?;
+        Ok(response)
+    }
+
+    /// Запрос на получение рекуррентных списаний по подписке.
+    ///
+    /// Возвращает заказы, созданные `recur_subscription` для стартового заказа подписки,
+    /// от самого раннего.
+    pub async fn list_subscription_charges(
+        &self,
+        customer_subscription_id: impl Into<String>,
+        request: ListSubscriptionChargesRequest,
+    ) -> R<SubscriptionChargesResponseData> {
+        let r = self
+            .endpoints()
+            .list_subscription_charges(&customer_subscription_id.into(), &request)?;
+        let response = self.send(r).await?;
         Ok(response)
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
 pub enum Method {
     Get,
     Post,
@@ -344,9 +423,12 @@ pub struct YandexPayApiRequest {
     #[into]
     //url
     pub url: S,
+    #[default(Vec::new())]
+    //Query parameters, encoded by the transport
+    pub query: Vec<(String, String)>,
     #[into]
     //Authorization Token
-    pub api_key: S,
+    pub api_key: SecretKey,
     #[into]
     #[default(default_request_id())]
     //Request Id
@@ -357,6 +439,37 @@ pub struct YandexPayApiRequest {
     #[default(0)]
     //Current attempt number
     pub request_attempt: u32,
+    #[default(false)]
+    //Safe to resend POST request (e.g. it carries externalOperationId or a unique orderId)
+    pub idempotent: bool,
+}
+
+impl YandexPayApiRequest {
+    pub fn is_idempotent(&self) -> bool {
+        self.method == Method::Get || self.idempotent
+    }
+
+    /// URL вместе с закодированными параметрами запроса
+    pub fn full_url(&self) -> String {
+        if self.query.is_empty() {
+            return self.url.to_string();
+        }
+        let query = form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(&self.query)
+            .finish();
+        format!("{}?{}", self.url, query)
+    }
+
+    /// HTTP-заголовки, которые нужно отправить вместе с запросом
+    pub fn headers(&self) -> [(&'static str, String); 5] {
+        [
+            ("Authorization", self.api_key.authorization()),
+            ("X-Request-Id", self.request_id.to_string()),
+            ("X-Request-Timeout", self.request_timeout.to_string()),
+            ("X-Request-Attempt", self.request_attempt.to_string()),
+            ("Content-Type", "application/json".to_string()),
+        ]
+    }
 }
 
 fn default_request_id() -> S {
@@ -364,42 +477,70 @@ fn default_request_id() -> S {
 }
 #[cfg(feature = "reqwest")]
 impl HttpClient for reqwest::Client {
-    fn send<T: serde::de::DeserializeOwned>(
-        &self,
-        request: YandexPayApiRequest,
-    ) -> impl Future<Output = R<T>> {
-        let client = self.clone();
+    async fn send<T: serde::de::DeserializeOwned>(&self, request: YandexPayApiRequest) -> R<T> {
+        self.send_raw(request).await?.decode()
+    }
+}
 
-        async move {
-            let body = request.body.clone();
+/// Заголовки запроса для reqwest. `Authorization` помечен как чувствительный,
+/// чтобы ключ API не попадал в `Debug` запроса.
+#[cfg(feature = "reqwest")]
+pub(crate) fn reqwest_headers(request: &YandexPayApiRequest) -> R<reqwest::header::HeaderMap> {
+    let mut headers = reqwest::header::HeaderMap::new();
+    for (name, value) in request.headers() {
+        let mut value =
+            reqwest::header::HeaderValue::try_from(value).map_err(YandexPayApiError::transport)?;
+        value.set_sensitive(name == "Authorization");
+        headers.insert(name, value);
+    }
+    Ok(headers)
+}
+
+#[cfg(feature = "reqwest")]
+impl RawHttpClient for reqwest::Client {
+    fn send_raw(&self, request: YandexPayApiRequest) -> RawFuture<'_> {
+        Box::pin(async move {
             let method = match request.method {
                 Method::Get => reqwest::Method::GET,
                 Method::Post => reqwest::Method::POST,
             };
-            let mut request_builder = client
+            let mut request_builder = self
                 .request(method, &*request.url)
-                .header("Authorization", format!("Api-Key {}", request.api_key))
-                .header("X-Request-Id", &*request.request_id)
-                .header("X-Request-Timeout", request.request_timeout.to_string())
-                .header("X-Request-Attempt", request.request_attempt.to_string())
-                .header("Content-Type", "application/json");
-            if let Some(body) = body {
+                .query(&request.query)
+                .timeout(Duration::from_millis(request.request_timeout.into()));
+            request_builder = request_builder.headers(reqwest_headers(&request)?);
+            if let Some(body) = request.body {
                 request_builder = request_builder.body(body);
             }
             let response = request_builder.send().await?;
+            let status = response.status().as_u16();
+            let body = response.bytes().await?;
+            Ok(RawResponse { status, body })
+        })
+    }
+}
 
-            if response.status().is_success() {
-                let result = response.text().await?;
-                let result = serde_json::from_str::<YandexPayApiResponse<T>>(&result)?;
-                Ok(result.data)
-            } else {
-                let error_message = response.text().await?;
-                tracing::error!("{}", error_message);
-                let error = serde_json::from_str::<YandexPayApiResponseError>(&error_message)?;
-                Err(YandexPayApiError::Api(error))
-            }
-        }
+/// Разбирает ответ API: поле `data` при успешном статусе, иначе [`YandexPayApiError::Api`].
+///
+/// Используется всеми встроенными реализациями [`HttpClient`].
+pub fn decode_response<T: serde::de::DeserializeOwned>(status: u16, body: &[u8]) -> R<T> {
+    if (200..300).contains(&status) {
+        let result = serde_json::from_slice::<YandexPayApiResponse<T>>(body)?;
+        return Ok(result.data);
     }
+    let error_message = String::from_utf8_lossy(body);
+    tracing::error!("{}", error_message);
+    let error = serde_json::from_slice::<YandexPayApiResponseError>(body).unwrap_or_else(|_| {
+        YandexPayApiResponseError {
+            message: error_message.into(),
+            ..Default::default()
+        }
+    });
+    Err(YandexPayApiError::Api(YandexPayApiResponseError {
+        code: error.code.or(Some(status as u32)),
+        http_status: Some(status),
+        ..error
+    }))
 }
 
 #[derive(Debug, serde::Deserialize)]
@@ -409,12 +550,25 @@ pub struct YandexPayApiResponse<T> {
     pub status: Option<String>,
 }
 
-#[derive(Debug, serde::Deserialize)]
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct YandexPayApiResponseError {
     pub code: Option<u32>,
     pub status: Option<String>,
     #[serde(default = "Default::default")]
     pub message: S,
+    #[serde(default)]
+    /// Машиночитаемый код причины ошибки
+    pub reason_code: Option<ReasonCode>,
+    #[serde(default)]
+    /// Описание причины ошибки
+    pub reason: Option<String>,
+    #[serde(default)]
+    /// Дополнительные сведения об ошибке
+    pub details: Option<Box<serde_json::Value>>,
+    #[serde(skip)]
+    /// HTTP-статус ответа
+    pub http_status: Option<u16>,
 }
 
 impl std::fmt::Display for YandexPayApiResponseError {
@@ -423,6 +577,41 @@ impl std::fmt::Display for YandexPayApiResponseError {
             f,
             "Yandex Pay API error: StatusCode: {:?}, Status: {:?}, Message: {}",
             self.code, self.status, self.message
-        )
+        )?;
+        if let Some(reason_code) = &self.reason_code {
+            write!(f, ", ReasonCode: {}", reason_code)?;
+        }
+        if let Some(reason) = &self.reason {
+            write!(f, ", Reason: {}", reason)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "reqwest"))]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_request_timeout_is_enforced() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (_socket, _) = listener.accept().await.unwrap();
+            tokio::time::sleep(Duration::from_secs(5)).await;
+        });
+        let api = YandexPayApi::new(base_url.into(), "key".into(), reqwest::Client::new());
+        let started = std::time::Instant::now();
+        let result = api
+            .with_request_timeout(Duration::from_millis(100))
+            .get_order("order")
+            .await;
+        assert!(
+            matches!(result, Err(YandexPayApiError::Timeout)),
+            "{result:?}"
+        );
+        assert!(started.elapsed() < Duration::from_secs(2));
+        assert_eq!(api.request_timeout, None);
+        server.abort();
     }
 }
@@ -0,0 +1,802 @@
+//! Обработка запросов Yandex Pay Checkout к продавцу (Merchant API).
+//!
+//! Yandex Pay вызывает `/v1/order/render`, `/v1/order/create` и `/v1/webhook` на стороне продавца,
+//! передавая в теле запроса JWT, подписанный ES256.
+//! <https://pay.yandex.ru/docs/ru/custom/backend/merchant-api/>
+
+use builder_pattern::Builder;
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+
+use crate::*;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+/// Тело запроса `/v1/order/render`
+/// <https://pay.yandex.ru/docs/ru/custom/backend/merchant-api/order/render>
+pub struct RenderOrderRequest {
+    pub merchant_id: String,
+    #[serde(deserialize_with = "request_cart")]
+    /// Корзина
+    pub cart: Cart,
+    /// Трехбуквенный код валюты заказа (ISO 4217)
+    pub currency_code: CurrencyCode,
+    #[serde(default)]
+    /// Идентификатор заказа на стороне продавца, если он уже был создан
+    pub order_id: Option<String>,
+    #[serde(default)]
+    /// Произвольные данные, переданные продавцом при инициализации кнопки
+    pub metadata: Option<String>,
+    #[serde(default)]
+    /// Выбранный способ оплаты
+    pub payment_method: Option<PaymentMethod>,
+    #[serde(default)]
+    /// Выбранный способ доставки
+    pub shipping_method: Option<ShippingMethod>,
+    #[serde(default)]
+    /// Адрес доставки
+    pub shipping_address: Option<Address>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Builder)]
+#[serde(rename_all = "camelCase")]
+/// Ответ продавца на `/v1/order/render`
+pub struct RenderOrderResponse {
+    /// Корзина с рассчитанными ценами и применёнными купонами
+    pub cart: Cart,
+    #[default(CurrencyCode::Rub)]
+    /// Трехбуквенный код валюты заказа (ISO 4217)
+    pub currency_code: CurrencyCode,
+    #[default(vec![AvailablePaymentMethod::Card])]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    /// Доступные методы оплаты
+    pub available_payment_methods: Vec<AvailablePaymentMethod>,
+    #[default(None)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Показывать ли поле для комментария к заказу
+    pub enable_comment_field: Option<bool>,
+    #[default(None)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Показывать ли поле для ввода купона
+    pub enable_coupons: Option<bool>,
+    #[default(None)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Произвольные данные по заказу для внутреннего использования
+    /// Max length: 2048
+    pub metadata: Option<String>,
+    #[default(None)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Итоговая стоимость заказа с учётом доставки
+    pub order_amount: Option<Money>,
+    #[default(None)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Данные покупателя, которые нужно запросить на форме
+    pub required_fields: Option<RequiredFields>,
+    #[default(None)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Доступные способы доставки
+    pub shipping: Option<ShippingOptions>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Builder)]
+#[serde(rename_all = "camelCase")]
+/// Доступные способы доставки
+pub struct ShippingOptions {
+    #[default(vec![])]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    /// Доступные типы доставки
+    pub available_methods: Vec<ShippingMethodType>,
+    #[default(vec![])]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    /// Варианты курьерской доставки
+    pub available_courier_options: Vec<CourierOption>,
+    #[default(vec![])]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    /// Точки самовывоза
+    pub available_pickup_options: Vec<PickupOption>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Builder)]
+#[serde(rename_all = "camelCase")]
+/// Данные покупателя, которые нужно запросить на форме
+pub struct RequiredFields {
+    #[default(None)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub billing_contact: Option<ContactFields>,
+    #[default(None)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shipping_contact: Option<ContactFields>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Builder)]
+#[serde(rename_all = "camelCase")]
+/// Поля контакта, обязательные для заполнения
+pub struct ContactFields {
+    #[default(false)]
+    pub email: bool,
+    #[default(false)]
+    pub name: bool,
+    #[default(false)]
+    pub phone: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+/// Адрес доставки
+pub struct Address {
+    /// Адрес одной строкой
+    pub address_line: Option<String>,
+    pub country: Option<String>,
+    pub region: Option<String>,
+    pub locality: Option<String>,
+    pub district: Option<String>,
+    pub street: Option<String>,
+    pub building: Option<String>,
+    pub room: Option<String>,
+    pub entrance: Option<String>,
+    pub floor: Option<String>,
+    pub intercom: Option<String>,
+    /// Комментарий покупателя к адресу
+    pub comment: Option<String>,
+    pub zip: Option<String>,
+    pub location: Option<Location>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+/// Контактные данные покупателя
+pub struct Contact {
+    pub id: Option<String>,
+    pub first_name: Option<String>,
+    pub second_name: Option<String>,
+    pub last_name: Option<String>,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+/// Тело запроса `/v1/order/create`
+/// <https://pay.yandex.ru/docs/ru/custom/backend/merchant-api/order/create>
+pub struct MerchantCreateOrderRequest {
+    pub merchant_id: String,
+    #[serde(deserialize_with = "request_cart")]
+    /// Корзина
+    pub cart: Cart,
+    /// Трехбуквенный код валюты заказа (ISO 4217)
+    pub currency_code: CurrencyCode,
+    /// Сумма заказа, которую увидел покупатель
+    pub order_amount: Money,
+    #[serde(default)]
+    /// Идентификатор заказа на стороне продавца, если он уже был создан
+    pub order_id: Option<String>,
+    #[serde(default)]
+    /// Произвольные данные, переданные продавцом
+    pub metadata: Option<String>,
+    #[serde(default)]
+    /// Выбранный способ оплаты
+    pub payment_method: Option<PaymentMethod>,
+    #[serde(default)]
+    /// Выбранный способ доставки
+    pub shipping_method: Option<ShippingMethod>,
+    #[serde(default)]
+    /// Адрес доставки
+    pub shipping_address: Option<Address>,
+    #[serde(default)]
+    pub billing_contact: Option<Contact>,
+    #[serde(default)]
+    pub shipping_contact: Option<Contact>,
+    #[serde(default)]
+    /// Комментарий покупателя к заказу
+    pub order_comment: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Builder)]
+#[serde(rename_all = "camelCase")]
+/// Ответ продавца на `/v1/order/create`
+pub struct MerchantCreateOrderResponse {
+    #[into]
+    /// Идентификатор созданного заказа на стороне продавца
+    pub order_id: String,
+    #[default(None)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Произвольные данные по заказу для внутреннего использования
+    pub metadata: Option<String>,
+    #[default(None)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Ссылки для переадресации пользователя после оплаты
+    pub redirect_urls: Option<MerchantRedirectUrls>,
+    #[default(None)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Время жизни заказа (сек)
+    pub ttl: Option<u32>,
+}
+
+/// Ошибка обработки запроса, возвращаемая Yandex Pay в теле ответа
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("Merchant error {status} {reason_code}: {reason:?}")]
+pub struct MerchantError {
+    /// HTTP-статус ответа
+    pub status: u16,
+    pub reason_code: String,
+    pub reason: Option<String>,
+}
+
+impl MerchantError {
+    /// Ошибка с HTTP-статусом 400
+    pub fn new(reason_code: impl Into<String>) -> Self {
+        MerchantError {
+            status: 400,
+            reason_code: reason_code.into(),
+            reason: None,
+        }
+    }
+
+    pub fn with_reason(mut self, reason: impl Into<String>) -> Self {
+        self.reason = Some(reason.into());
+        self
+    }
+
+    pub fn with_status(mut self, status: u16) -> Self {
+        self.status = status;
+        self
+    }
+}
+
+/// Обработчик запросов Yandex Pay к продавцу
+pub trait MerchantHandler {
+    /// Расчёт корзины, доставки и доступных способов оплаты
+    fn render_order(
+        &self,
+        request: RenderOrderRequest,
+    ) -> impl Future<Output = R<RenderOrderResponse, MerchantError>> + Send;
+
+    /// Создание заказа на стороне продавца перед оплатой
+    fn create_order(
+        &self,
+        request: MerchantCreateOrderRequest,
+    ) -> impl Future<Output = R<MerchantCreateOrderResponse, MerchantError>> + Send;
+
+    /// Уведомление об изменении статуса заказа, операции или подписки
+    fn webhook(
+        &self,
+        notification: WebhookNotification,
+    ) -> impl Future<Output = R<(), MerchantError>> + Send {
+        let _ = notification;
+        async { Ok(()) }
+    }
+}
+
+/// HTTP-ответ на запрос Yandex Pay. Тело всегда в формате JSON.
+#[derive(Debug, Clone)]
+pub struct MerchantResponse {
+    pub status: u16,
+    pub body: Bytes,
+}
+
+impl MerchantResponse {
+    fn success<T: Serialize>(data: Option<T>) -> Self {
+        let body = match data {
+            Some(data) => serde_json::to_vec(&serde_json::json!({
+                "status": "success",
+                "data": data,
+            })),
+            None => serde_json::to_vec(&serde_json::json!({ "status": "success" })),
+        };
+        match body {
+            Ok(body) => MerchantResponse {
+                status: 200,
+                body: body.into(),
+            },
+            Err(err) => MerchantResponse::fail(
+                &MerchantError::new("INTERNAL_SERVER_ERROR")
+                    .with_status(500)
+                    .with_reason(err.to_string()),
+            ),
+        }
+    }
+
+    fn fail(error: &MerchantError) -> Self {
+        let body = serde_json::json!({
+            "status": "fail",
+            "reasonCode": error.reason_code,
+            "reason": error.reason,
+        });
+        MerchantResponse {
+            status: error.status,
+            body: body.to_string().into(),
+        }
+    }
+}
+
+/// Маршрутизирует запросы Yandex Pay к [`MerchantHandler`], не завися от HTTP-фреймворка.
+///
+/// Принимает путь и сырое тело запроса (JWT), проверяет подпись через [`WebhookVerifier`]
+/// и возвращает JSON-ответ в формате Merchant API.
+#[derive(Debug, Clone)]
+pub struct MerchantDispatcher<H> {
+    pub verifier: WebhookVerifier,
+    pub handler: H,
+}
+
+impl<H: MerchantHandler> MerchantDispatcher<H> {
+    pub fn new(verifier: WebhookVerifier, handler: H) -> Self {
+        MerchantDispatcher { verifier, handler }
+    }
+
+    /// Обрабатывает запрос. Путь может содержать префикс, например `/yandex/v1/order/render`.
+    pub async fn dispatch(&self, path: &str, body: &[u8]) -> MerchantResponse {
+        self.dispatch_at(path, body, chrono::Utc::now()).await
+    }
+
+    /// То же, что [`MerchantDispatcher::dispatch`], но проверяет срок действия JWT
+    /// относительно переданного момента времени.
+    pub async fn dispatch_at(&self, path: &str, body: &[u8], now: Time) -> MerchantResponse {
+        let path = path.trim_end_matches('/');
+        let result = if path.ends_with("/v1/order/render") {
+            match verify_body(&self.verifier, body, now) {
+                Ok(request) => self.handler.render_order(request).await.map(Some),
+                Err(err) => Err(err),
+            }
+            .map(MerchantResponse::success)
+        } else if path.ends_with("/v1/order/create") {
+            match verify_body(&self.verifier, body, now) {
+                Ok(request) => self.handler.create_order(request).await.map(Some),
+                Err(err) => Err(err),
+            }
+            .map(MerchantResponse::success)
+        } else if path.ends_with("/v1/webhook") {
+            match verify_body(&self.verifier, body, now) {
+                Ok(notification) => self.handler.webhook(notification).await,
+                Err(err) => Err(err),
+            }
+            .map(|()| MerchantResponse::success(None::<()>))
+        } else {
+            Err(MerchantError::new("NOT_FOUND").with_status(404))
+        };
+        result.unwrap_or_else(|err| {
+            tracing::warn!("Yandex Pay merchant request to {} failed: {}", path, err);
+            MerchantResponse::fail(&err)
+        })
+    }
+}
+
+/// Корзина из запроса Yandex Pay к продавцу. В ней может не быть `total`: сумму
+/// рассчитывает продавец, поэтому отсутствующая сумма считается нулевой.
+fn request_cart<'de, D: serde::Deserializer<'de>>(deserializer: D) -> R<Cart, D::Error> {
+    let mut cart = serde_json::Value::deserialize(deserializer)?;
+    if let Some(fields) = cart.as_object_mut()
+        && !fields.contains_key("total")
+    {
+        let total = serde_json::to_value(CartTotal::default()).map_err(serde::de::Error::custom)?;
+        fields.insert("total".into(), total);
+    }
+    Cart::deserialize(cart).map_err(serde::de::Error::custom)
+}
+
+fn verify_body<T: serde::de::DeserializeOwned>(
+    verifier: &WebhookVerifier,
+    body: &[u8],
+    now: Time,
+) -> R<T, MerchantError> {
+    let token = std::str::from_utf8(body)
+        .map_err(|_| MerchantError::new("BAD_REQUEST").with_reason("request body is not a JWT"))?;
+    verifier
+        .verify_payload_at(token, now)
+        .map_err(|err| match err {
+            WebhookError::Serde(err) => {
+                MerchantError::new("BAD_REQUEST").with_reason(err.to_string())
+            }
+            err => MerchantError::new("FORBIDDEN")
+                .with_status(403)
+                .with_reason(err.to_string()),
+        })
+}
+
+/// Сведения об уведомлении, общие для всех событий
+#[derive(Debug, Clone)]
+pub struct WebhookContext {
+    /// Исходное тело запроса (JWT) для аудита
+    pub raw_body: Bytes,
+    pub merchant_id: String,
+    pub event_time: Time,
+}
+
+/// Обработчик уведомлений `/v1/webhook` с отдельным методом для каждого события.
+///
+/// Если метод возвращает ошибку, Yandex Pay получает ответ с её HTTP-статусом и повторит
+/// уведомление позже. По умолчанию события игнорируются.
+pub trait WebhookHandler {
+    fn on_order_status_updated(
+        &self,
+        order: OrderWebhookData,
+        context: WebhookContext,
+    ) -> impl Future<Output = R<(), MerchantError>> + Send {
+        let _ = (order, context);
+        async { Ok(()) }
+    }
+
+    fn on_operation_status_updated(
+        &self,
+        operation: OperationWebhookData,
+        context: WebhookContext,
+    ) -> impl Future<Output = R<(), MerchantError>> + Send {
+        let _ = (operation, context);
+        async { Ok(()) }
+    }
+
+    fn on_subscription_status_updated(
+        &self,
+        subscription: SubscriptionWebhookData,
+        context: WebhookContext,
+    ) -> impl Future<Output = R<(), MerchantError>> + Send {
+        let _ = (subscription, context);
+        async { Ok(()) }
+    }
+}
+
+/// Проверяет уведомления `/v1/webhook` и передаёт их [`WebhookHandler`].
+#[derive(Debug, Clone)]
+pub struct WebhookDispatcher<H> {
+    pub verifier: WebhookVerifier,
+    pub handler: H,
+}
+
+impl<H: WebhookHandler> WebhookDispatcher<H> {
+    pub fn new(verifier: WebhookVerifier, handler: H) -> Self {
+        WebhookDispatcher { verifier, handler }
+    }
+
+    /// Обрабатывает уведомление. Тело передаётся обработчику в [`WebhookContext::raw_body`].
+    pub async fn dispatch(&self, body: Bytes) -> MerchantResponse {
+        self.dispatch_at(body, chrono::Utc::now()).await
+    }
+
+    /// То же, что [`WebhookDispatcher::dispatch`], но проверяет срок действия JWT
+    /// относительно переданного момента времени.
+    pub async fn dispatch_at(&self, body: Bytes, now: Time) -> MerchantResponse {
+        let notification = match verify_body::<WebhookNotification>(&self.verifier, &body, now) {
+            Ok(notification) => notification,
+            Err(err) => return MerchantResponse::fail(&err),
+        };
+        let context = WebhookContext {
+            raw_body: body,
+            merchant_id: notification.merchant_id,
+            event_time: notification.event_time,
+        };
+        let result = match notification.event {
+            WebhookEvent::OrderStatusUpdated { order } => {
+                self.handler.on_order_status_updated(order, context).await
+            }
+            WebhookEvent::OperationStatusUpdated { operation } => {
+                self.handler
+                    .on_operation_status_updated(operation, context)
+                    .await
+            }
+            WebhookEvent::SubscriptionStatusUpdated { subscription } => {
+                self.handler
+                    .on_subscription_status_updated(subscription, context)
+                    .await
+            }
+            WebhookEvent::Unknown => {
+                tracing::debug!("Ignoring unknown Yandex Pay webhook event");
+                Ok(())
+            }
+        };
+        match result {
+            Ok(()) => MerchantResponse::success(None::<()>),
+            Err(err) => {
+                tracing::warn!("Yandex Pay webhook handler failed: {}", err);
+                MerchantResponse::fail(&err)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::webhook::tests::{jwk, sign, signing_key};
+
+    pub(crate) const MERCHANT_ID: &str = "merchant";
+
+    pub(crate) fn now() -> Time {
+        chrono::DateTime::from_timestamp(1_750_000_000, 0).unwrap()
+    }
+
+    pub(crate) fn verifier() -> WebhookVerifier {
+        WebhookVerifier::new(
+            Jwks {
+                keys: vec![jwk(&signing_key(1), "key-1")],
+            },
+            MERCHANT_ID.into(),
+        )
+    }
+
+    /// Подписывает тело запроса ключом из [`verifier`]
+    pub(crate) fn token(payload: serde_json::Value) -> String {
+        token_at(payload, now())
+    }
+
+    /// Как [`token`], но действителен в момент вызова
+    #[cfg(any(feature = "axum", feature = "actix"))]
+    pub(crate) fn fresh_token(payload: serde_json::Value) -> String {
+        token_at(payload, chrono::Utc::now())
+    }
+
+    fn token_at(mut payload: serde_json::Value, now: Time) -> String {
+        let claims = payload.as_object_mut().unwrap();
+        claims.insert("merchantId".into(), MERCHANT_ID.into());
+        claims.insert("iat".into(), now.timestamp().into());
+        claims.insert("exp".into(), (now.timestamp() + 300).into());
+        sign(&signing_key(1), "key-1", &payload)
+    }
+
+    pub(crate) fn cart_json() -> serde_json::Value {
+        serde_json::json!({
+            "items": [{"productId": "p1", "quantity": {"count": "2"}}],
+            "coupons": [{"value": "SALE"}]
+        })
+    }
+
+    pub(crate) fn order_event(order_id: &str) -> serde_json::Value {
+        serde_json::json!({
+            "eventTime": "2025-06-15T15:06:40Z",
+            "event": "ORDER_STATUS_UPDATED",
+            "order": {"orderId": order_id, "paymentStatus": "CAPTURED"}
+        })
+    }
+
+    /// Обработчик для тестов маршрутов axum и actix: запоминает уведомления о заказах,
+    /// возвращает корзину без изменений и отклоняет создание заказа
+    #[derive(Clone, Default)]
+    pub(crate) struct RecordingShop {
+        pub(crate) orders: Arc<Mutex<Vec<(String, Bytes)>>>,
+    }
+
+    impl WebhookHandler for RecordingShop {
+        async fn on_order_status_updated(
+            &self,
+            order: OrderWebhookData,
+            context: WebhookContext,
+        ) -> R<(), MerchantError> {
+            if order.order_id == "broken" {
+                return Err(MerchantError::new("INTERNAL_SERVER_ERROR").with_status(503));
+            }
+            self.orders
+                .lock()
+                .unwrap()
+                .push((order.order_id, context.raw_body));
+            Ok(())
+        }
+    }
+
+    impl MerchantHandler for RecordingShop {
+        async fn render_order(
+            &self,
+            request: RenderOrderRequest,
+        ) -> R<RenderOrderResponse, MerchantError> {
+            Ok(RenderOrderResponse::new().cart(request.cart).build())
+        }
+
+        async fn create_order(
+            &self,
+            _: MerchantCreateOrderRequest,
+        ) -> R<MerchantCreateOrderResponse, MerchantError> {
+            Err(MerchantError::new("ORDER_DETAILS_MISMATCH"))
+        }
+    }
+
+    #[derive(Clone, Default)]
+    struct Shop {
+        notifications: Arc<Mutex<Vec<WebhookNotification>>>,
+    }
+
+    impl MerchantHandler for Shop {
+        async fn render_order(
+            &self,
+            request: RenderOrderRequest,
+        ) -> R<RenderOrderResponse, MerchantError> {
+            let mut cart = request.cart;
+            for item in &mut cart.items {
+                item.unit_price = Some(Money::from_rubles(100));
+                item.total = Some(Money::from_rubles(100).mul_quantity(item.quantity.count));
+            }
+            for coupon in &mut cart.coupons {
+                coupon.status = Some(CouponStatus::Invalid);
+            }
+            cart.total.amount = cart.items.iter().filter_map(|item| item.total).sum();
+            Ok(RenderOrderResponse::new()
+                .cart(cart)
+                .shipping(Some(
+                    ShippingOptions::new()
+                        .available_methods(vec![ShippingMethodType::Pickup])
+                        .build(),
+                ))
+                .build())
+        }
+
+        async fn create_order(
+            &self,
+            request: MerchantCreateOrderRequest,
+        ) -> R<MerchantCreateOrderResponse, MerchantError> {
+            if request.order_amount != Money::from_rubles(200) {
+                return Err(MerchantError::new("ORDER_AMOUNT_MISMATCH"));
+            }
+            Ok(MerchantCreateOrderResponse::new()
+                .order_id("order-1")
+                .build())
+        }
+
+        async fn webhook(&self, notification: WebhookNotification) -> R<(), MerchantError> {
+            self.notifications.lock().unwrap().push(notification);
+            Ok(())
+        }
+    }
+
+    fn dispatcher() -> MerchantDispatcher<Shop> {
+        MerchantDispatcher::new(verifier(), Shop::default())
+    }
+
+    fn json(response: &MerchantResponse) -> serde_json::Value {
+        serde_json::from_slice(&response.body).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_render_order() {
+        let body = token(serde_json::json!({ "cart": cart_json(), "currencyCode": "RUB" }));
+        let response = dispatcher()
+            .dispatch_at("/v1/order/render", body.as_bytes(), now())
+            .await;
+        assert_eq!(response.status, 200);
+        let json = json(&response);
+        assert_eq!(json["status"], "success");
+        assert_eq!(json["data"]["cart"]["total"]["amount"], "200.00");
+        assert_eq!(json["data"]["cart"]["coupons"][0]["status"], "INVALID");
+        assert_eq!(json["data"]["shipping"]["availableMethods"][0], "PICKUP");
+    }
+
+    #[tokio::test]
+    async fn test_create_order() {
+        let dispatcher = dispatcher();
+        let request = |amount: &str| {
+            token(serde_json::json!({
+                "cart": cart_json(),
+                "currencyCode": "RUB",
+                "orderAmount": amount,
+                "shippingMethod": {"methodType": "PICKUP"},
+                "paymentMethod": {"methodType": "CARD"}
+            }))
+        };
+        let response = dispatcher
+            .dispatch_at(
+                "/shop/v1/order/create/",
+                request("200.00").as_bytes(),
+                now(),
+            )
+            .await;
+        assert_eq!(response.status, 200);
+        assert_eq!(json(&response)["data"]["orderId"], "order-1");
+
+        let response = dispatcher
+            .dispatch_at("/v1/order/create", request("150.00").as_bytes(), now())
+            .await;
+        assert_eq!(response.status, 400);
+        assert_eq!(json(&response)["status"], "fail");
+        assert_eq!(json(&response)["reasonCode"], "ORDER_AMOUNT_MISMATCH");
+    }
+
+    #[tokio::test]
+    async fn test_webhook_and_rejections() {
+        let dispatcher = dispatcher();
+        let body = token(serde_json::json!({
+            "eventTime": "2025-06-15T15:06:40Z",
+            "event": "ORDER_STATUS_UPDATED",
+            "order": {"orderId": "order-1", "paymentStatus": "CAPTURED"}
+        }));
+        let response = dispatcher
+            .dispatch_at("/v1/webhook", body.as_bytes(), now())
+            .await;
+        assert_eq!(response.status, 200);
+        assert_eq!(json(&response), serde_json::json!({"status": "success"}));
+        assert_eq!(dispatcher.handler.notifications.lock().unwrap().len(), 1);
+
+        let response = dispatcher
+            .dispatch_at("/v1/webhook", b"not-a-jwt", now())
+            .await;
+        assert_eq!(response.status, 403);
+        let response = dispatcher
+            .dispatch_at("/v1/unknown", body.as_bytes(), now())
+            .await;
+        assert_eq!(response.status, 404);
+    }
+
+    #[tokio::test]
+    async fn test_webhook_dispatcher() {
+        let shop = RecordingShop::default();
+        let dispatcher = WebhookDispatcher::new(verifier(), shop.clone());
+        let body = token(order_event("order-1"));
+        let response = dispatcher.dispatch_at(body.clone().into(), now()).await;
+        assert_eq!(response.status, 200);
+        assert_eq!(
+            *shop.orders.lock().unwrap(),
+            vec![("order-1".to_string(), Bytes::from(body))]
+        );
+
+        let response = dispatcher
+            .dispatch_at(token(order_event("broken")).into(), now())
+            .await;
+        assert_eq!(response.status, 503);
+        assert_eq!(json(&response)["reasonCode"], "INTERNAL_SERVER_ERROR");
+
+        let response = dispatcher.dispatch_at("garbage".into(), now()).await;
+        assert_eq!(response.status, 403);
+        assert_eq!(json(&response)["reasonCode"], "FORBIDDEN");
+    }
+
+    #[tokio::test]
+    async fn test_unknown_values() {
+        let dispatcher = dispatcher();
+        let cart = serde_json::json!({
+            "items": [{
+                "productId": "p1",
+                "quantity": {"count": "2"},
+                "receipt": {"tax": 42, "measure": 99}
+            }],
+            "coupons": [{"value": "SALE", "status": "USED"}]
+        });
+        let payload = serde_json::json!({
+            "cart": cart,
+            "currencyCode": "USD",
+            "orderAmount": "200.00",
+            "paymentMethod": {"methodType": "BNPL", "cardNetwork": "NEWCARD"},
+            "shippingMethod": {"methodType": "DRONE"}
+        });
+        let mut request = payload.clone();
+        request["merchantId"] = MERCHANT_ID.into();
+        let request: MerchantCreateOrderRequest = serde_json::from_value(request).unwrap();
+        assert_eq!(request.currency_code, CurrencyCode::Unknown("USD".into()));
+        assert_eq!(
+            request.payment_method.unwrap().method_type,
+            MethodType::Unknown("BNPL".into())
+        );
+        let receipt = request.cart.items[0].receipt.as_ref().unwrap();
+        assert_eq!(receipt.tax, Tax::Unknown(42));
+
+        for path in ["/v1/order/render", "/v1/order/create"] {
+            let response = dispatcher
+                .dispatch_at(path, token(payload.clone()).as_bytes(), now())
+                .await;
+            assert_eq!(response.status, 200, "{path}");
+        }
+
+        let mut cart = cart_json();
+        cart["total"] = serde_json::json!({"amount": "200.00"});
+        let response: RenderOrderResponse = serde_json::from_value(serde_json::json!({
+            "cart": cart,
+            "currencyCode": "RUB",
+            "availablePaymentMethods": ["CARD", "BNPL"]
+        }))
+        .unwrap();
+        assert_eq!(
+            response.available_payment_methods,
+            vec![
+                AvailablePaymentMethod::Card,
+                AvailablePaymentMethod::Unknown("BNPL".into())
+            ]
+        );
+
+        let body = token(serde_json::json!({
+            "eventTime": "2025-06-15T15:06:40Z",
+            "event": "CHARGEBACK_OPENED",
+        }));
+        let response = dispatcher
+            .dispatch_at("/v1/webhook", body.as_bytes(), now())
+            .await;
+        assert_eq!(response.status, 200);
+        let notifications = dispatcher.handler.notifications.lock().unwrap();
+        assert!(matches!(notifications[0].event, WebhookEvent::Unknown));
+    }
+}
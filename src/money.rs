@@ -0,0 +1,252 @@
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Денежная сумма в копейках.
+///
+/// В API передаётся строкой с не более чем двумя знаками после запятой, например `"123.45"`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Money(i64);
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum MoneyParseError {
+    #[error("Invalid money amount: {0:?}")]
+    Invalid(String),
+    #[error("Money amount has more than two fraction digits: {0:?}")]
+    TooPrecise(String),
+    #[error("Money amount is out of range: {0:?}")]
+    Overflow(String),
+}
+
+impl Money {
+    pub const ZERO: Money = Money(0);
+
+    pub const fn from_kopecks(kopecks: i64) -> Self {
+        Money(kopecks)
+    }
+
+    pub const fn from_rubles(rubles: i64) -> Self {
+        Money(rubles * 100)
+    }
+
+    pub const fn kopecks(self) -> i64 {
+        self.0
+    }
+
+    pub const fn is_zero(self) -> bool {
+        self.0 == 0
+    }
+
+    pub const fn is_negative(self) -> bool {
+        self.0 < 0
+    }
+
+    pub fn checked_add(self, other: Money) -> Option<Money> {
+        self.0.checked_add(other.0).map(Money)
+    }
+
+    pub fn checked_sub(self, other: Money) -> Option<Money> {
+        self.0.checked_sub(other.0).map(Money)
+    }
+
+    /// Стоимость `quantity` единиц по цене `self`, округлённая до копейки.
+    pub fn mul_quantity(self, quantity: f64) -> Money {
+        Money((self.0 as f64 * quantity).round() as i64)
+    }
+
+    /// Приближённое значение в рублях, только для отображения.
+    pub fn to_f64(self) -> f64 {
+        self.0 as f64 / 100.0
+    }
+}
+
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let abs = self.0.unsigned_abs();
+        write!(f, "{}{}.{:02}", sign, abs / 100, abs % 100)
+    }
+}
+
+impl FromStr for Money {
+    type Err = MoneyParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || MoneyParseError::Invalid(s.to_string());
+        let (negative, unsigned) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s),
+        };
+        let (int_part, frac_part) = match unsigned.split_once('.') {
+            Some((int_part, frac_part)) => (int_part, frac_part),
+            None => (unsigned, ""),
+        };
+        let is_digits = |part: &str| part.bytes().all(|b| b.is_ascii_digit());
+        if int_part.is_empty() || !is_digits(int_part) || !is_digits(frac_part) {
+            return Err(invalid());
+        }
+        if unsigned.ends_with('.') {
+            return Err(invalid());
+        }
+        if frac_part.len() > 2 {
+            return Err(MoneyParseError::TooPrecise(s.to_string()));
+        }
+        let overflow = || MoneyParseError::Overflow(s.to_string());
+        let rubles: i64 = int_part.parse().map_err(|_| overflow())?;
+        let kopecks: i64 = format!("{:0<2}", frac_part)
+            .parse()
+            .map_err(|_| invalid())?;
+        let value = rubles
+            .checked_mul(100)
+            .and_then(|v| v.checked_add(kopecks))
+            .ok_or_else(overflow)?;
+        Ok(Money(if negative { -value } else { value }))
+    }
+}
+
+impl Serialize for Money {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Money {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct MoneyVisitor;
+
+        impl serde::de::Visitor<'_> for MoneyVisitor {
+            type Value = Money;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a money amount with at most two fraction digits")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Money, E> {
+                v.parse().map_err(E::custom)
+            }
+
+            fn visit_i64<E: serde::de::Error>(self, v: i64) -> Result<Money, E> {
+                v.checked_mul(100)
+                    .map(Money)
+                    .ok_or_else(|| E::custom(MoneyParseError::Overflow(v.to_string())))
+            }
+
+            fn visit_u64<E: serde::de::Error>(self, v: u64) -> Result<Money, E> {
+                i64::try_from(v)
+                    .map_err(|_| E::custom(MoneyParseError::Overflow(v.to_string())))
+                    .and_then(|v| self.visit_i64(v))
+            }
+
+            fn visit_f64<E: serde::de::Error>(self, v: f64) -> Result<Money, E> {
+                self.visit_str(&v.to_string())
+            }
+        }
+
+        deserializer.deserialize_any(MoneyVisitor)
+    }
+}
+
+impl Add for Money {
+    type Output = Money;
+
+    fn add(self, other: Money) -> Money {
+        Money(self.0 + other.0)
+    }
+}
+
+impl AddAssign for Money {
+    fn add_assign(&mut self, other: Money) {
+        self.0 += other.0;
+    }
+}
+
+impl Sub for Money {
+    type Output = Money;
+
+    fn sub(self, other: Money) -> Money {
+        Money(self.0 - other.0)
+    }
+}
+
+impl SubAssign for Money {
+    fn sub_assign(&mut self, other: Money) {
+        self.0 -= other.0;
+    }
+}
+
+impl Neg for Money {
+    type Output = Money;
+
+    fn neg(self) -> Money {
+        Money(-self.0)
+    }
+}
+
+impl Sum for Money {
+    fn sum<I: Iterator<Item = Money>>(iter: I) -> Money {
+        iter.fold(Money::ZERO, Add::add)
+    }
+}
+
+impl<'a> Sum<&'a Money> for Money {
+    fn sum<I: Iterator<Item = &'a Money>>(iter: I) -> Money {
+        iter.copied().sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_display() {
+        assert_eq!("123.45".parse::<Money>().unwrap().kopecks(), 12345);
+        assert_eq!("10".parse::<Money>().unwrap().kopecks(), 1000);
+        assert_eq!("5.1".parse::<Money>().unwrap().kopecks(), 510);
+        assert_eq!("-0.50".parse::<Money>().unwrap().kopecks(), -50);
+        assert_eq!(Money::from_kopecks(510).to_string(), "5.10");
+        assert_eq!(Money::from_kopecks(-5).to_string(), "-0.05");
+        assert!(matches!(
+            "1.234".parse::<Money>(),
+            Err(MoneyParseError::TooPrecise(_))
+        ));
+        for invalid in ["", "abc", "1.", ".5", "1e3", "+1", "1.-1"] {
+            assert!(
+                matches!(invalid.parse::<Money>(), Err(MoneyParseError::Invalid(_))),
+                "{invalid:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_serde() {
+        let amount: Money = serde_json::from_str(r#""0.30""#).unwrap();
+        assert_eq!(amount, Money::from_kopecks(10) + Money::from_kopecks(20));
+        assert_eq!(serde_json::to_string(&amount).unwrap(), r#""0.30""#);
+        let amount: Money = serde_json::from_str("123.45").unwrap();
+        assert_eq!(amount.kopecks(), 12345);
+        let amount: Money = serde_json::from_str("7").unwrap();
+        assert_eq!(amount, Money::from_rubles(7));
+        assert!(serde_json::from_str::<Money>(r#""0.001""#).is_err());
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let price = Money::from_kopecks(3333);
+        assert_eq!(price.mul_quantity(3.0), Money::from_kopecks(9999));
+        assert_eq!(price.mul_quantity(0.5), Money::from_kopecks(1667));
+        let total: Money = [price, price].iter().sum();
+        assert_eq!(total - price, price);
+        assert!(Money::ZERO < price);
+        assert_eq!(price.checked_add(Money::from_kopecks(i64::MAX)), None);
+    }
+}
@@ -0,0 +1,375 @@
+//! Типизированный жизненный цикл заказа.
+//!
+//! [`OrderHandle`] хранит статус оплаты в параметре типа, поэтому, например, `capture`
+//! нельзя вызвать для заказа в статусе `CAPTURED`: метода просто нет. Каждый переход
+//! заново запрашивает заказ и возвращает [`OrderStage`] с фактическим статусом.
+
+use std::marker::PhantomData;
+
+use crate::*;
+
+/// Маркеры статусов оплаты для [`OrderHandle`]
+pub mod order_state {
+    mod sealed {
+        pub trait Sealed {}
+    }
+
+    /// Статус оплаты на уровне типов
+    pub trait OrderState: sealed::Sealed {}
+
+    macro_rules! states {
+        ($($(#[$doc:meta])* $name:ident),* $(,)?) => {$(
+            $(#[$doc])*
+            #[derive(Debug, Clone, Copy)]
+            pub struct $name;
+            impl sealed::Sealed for $name {}
+            impl OrderState for $name {}
+        )*};
+    }
+
+    states! {
+        /// `PENDING` — ожидает оплаты
+        Pending,
+        /// `AUTHORIZED` — средства заблокированы
+        Authorized,
+        /// `CAPTURED` — средства списаны
+        Captured,
+        /// `PARTIALLY_REFUNDED` — выполнен частичный возврат
+        PartiallyRefunded,
+        /// `CONFIRMED` — Сплит с оплатой при получении ждёт подтверждения
+        Confirmed,
+        /// `VOIDED` — платёж отменён
+        Voided,
+        /// `REFUNDED` — выполнен полный возврат
+        Refunded,
+        /// `FAILED` — оплата не прошла или заказ откатан
+        Failed,
+        /// Статус отсутствует или неизвестен клиенту
+        Unexpected,
+    }
+
+    /// Статусы, для которых доступен возврат
+    pub trait Refundable: OrderState {}
+    impl Refundable for Captured {}
+    impl Refundable for PartiallyRefunded {}
+}
+
+use order_state::*;
+
+/// Заказ с известным на уровне типов статусом оплаты.
+///
+/// Методы переходов существуют только для статусов, в которых их разрешает API:
+/// `capture` и `cancel` — для `AUTHORIZED`, `refund` — для `CAPTURED` и
+/// `PARTIALLY_REFUNDED`, `submit` — для `CONFIRMED`, `rollback` — для любого статуса.
+///
+/// ```compile_fail
+/// # use yandex_pay_api::{order_state::Captured, *};
+/// # async fn f<C: HttpClient>(order: OrderHandle<'_, C, Captured>) {
+/// order.capture(CaptureOrderRequest::new().build()).await;
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct OrderHandle<'a, C: HttpClient, State: OrderState> {
+    api: &'a YandexPayApi<C>,
+    order_id: String,
+    data: OrderResponseData,
+    state: PhantomData<State>,
+}
+
+/// Заказ с проверенным статусом оплаты.
+///
+/// [`OrderStage::Unexpected`] — запасной вариант для заказа без статуса или со статусом,
+/// которого нет в [`PaymentStatus`]: доступны только данные заказа, `rollback` и
+/// обычные методы [`YandexPayApi`].
+#[derive(Debug)]
+pub enum OrderStage<'a, C: HttpClient> {
+    Pending(OrderHandle<'a, C, Pending>),
+    Authorized(OrderHandle<'a, C, Authorized>),
+    Captured(OrderHandle<'a, C, Captured>),
+    PartiallyRefunded(OrderHandle<'a, C, PartiallyRefunded>),
+    Confirmed(OrderHandle<'a, C, Confirmed>),
+    Voided(OrderHandle<'a, C, Voided>),
+    Refunded(OrderHandle<'a, C, Refunded>),
+    Failed(OrderHandle<'a, C, Failed>),
+    Unexpected(OrderHandle<'a, C, Unexpected>),
+}
+
+impl<'a, C: HttpClient> OrderStage<'a, C> {
+    fn new(api: &'a YandexPayApi<C>, order_id: String, data: OrderResponseData) -> Self {
+        let status = data
+            .order
+            .as_ref()
+            .and_then(|order| order.payment_status.clone());
+        let handle = OrderHandle {
+            api,
+            order_id,
+            data,
+            state: PhantomData,
+        };
+        match status {
+            Some(PaymentStatus::Pending) => OrderStage::Pending(handle.cast()),
+            Some(PaymentStatus::Authorized) => OrderStage::Authorized(handle.cast()),
+            Some(PaymentStatus::Captured) => OrderStage::Captured(handle.cast()),
+            Some(PaymentStatus::PartiallyRefunded) => OrderStage::PartiallyRefunded(handle.cast()),
+            Some(PaymentStatus::Confirmed) => OrderStage::Confirmed(handle.cast()),
+            Some(PaymentStatus::Voided) => OrderStage::Voided(handle.cast()),
+            Some(PaymentStatus::Refunded) => OrderStage::Refunded(handle.cast()),
+            Some(PaymentStatus::Failed) => OrderStage::Failed(handle.cast()),
+            Some(PaymentStatus::Unknown(_)) | None => OrderStage::Unexpected(handle),
+        }
+    }
+
+    /// Данные заказа из последнего `get_order`
+    pub fn data(&self) -> &OrderResponseData {
+        match self {
+            OrderStage::Pending(handle) => handle.data(),
+            OrderStage::Authorized(handle) => handle.data(),
+            OrderStage::Captured(handle) => handle.data(),
+            OrderStage::PartiallyRefunded(handle) => handle.data(),
+            OrderStage::Confirmed(handle) => handle.data(),
+            OrderStage::Voided(handle) => handle.data(),
+            OrderStage::Refunded(handle) => handle.data(),
+            OrderStage::Failed(handle) => handle.data(),
+            OrderStage::Unexpected(handle) => handle.data(),
+        }
+    }
+
+    /// Статус оплаты из последнего `get_order`
+    pub fn payment_status(&self) -> Option<&PaymentStatus> {
+        self.data().order.as_ref()?.payment_status.as_ref()
+    }
+}
+
+impl<'a, C: HttpClient, State: OrderState> OrderHandle<'a, C, State> {
+    fn cast<Next: OrderState>(self) -> OrderHandle<'a, C, Next> {
+        OrderHandle {
+            api: self.api,
+            order_id: self.order_id,
+            data: self.data,
+            state: PhantomData,
+        }
+    }
+
+    pub fn order_id(&self) -> &str {
+        &self.order_id
+    }
+
+    /// Данные заказа из последнего `get_order`
+    pub fn data(&self) -> &OrderResponseData {
+        &self.data
+    }
+
+    pub fn into_data(self) -> OrderResponseData {
+        self.data
+    }
+
+    /// Клиент для запросов, которых нет у [`OrderHandle`]
+    pub fn api(&self) -> &'a YandexPayApi<C> {
+        self.api
+    }
+
+    /// Заново запрашивает заказ
+    pub async fn refresh(self) -> R<OrderStage<'a, C>> {
+        self.api.order_stage(self.order_id).await
+    }
+
+    /// Отменяет заказ в любом статусе, см. [`YandexPayApi::rollback_order`]
+    pub async fn rollback(self) -> R<OrderStage<'a, C>> {
+        self.api.rollback_order(&*self.order_id).await?;
+        self.refresh().await
+    }
+}
+
+impl<'a, C: HttpClient> OrderHandle<'a, C, Authorized> {
+    /// Списывает средства, см. [`YandexPayApi::capture_order`]
+    pub async fn capture(self, request: CaptureOrderRequest) -> R<OrderStage<'a, C>> {
+        self.api.capture_order(&*self.order_id, request).await?;
+        self.refresh().await
+    }
+
+    /// Отменяет платёж, см. [`YandexPayApi::cancel_order`]
+    pub async fn cancel(self, request: CancelOrderRequest) -> R<OrderStage<'a, C>> {
+        self.api.cancel_order(&*self.order_id, request).await?;
+        self.refresh().await
+    }
+}
+
+impl<'a, C: HttpClient, State: Refundable> OrderHandle<'a, C, State> {
+    /// Запускает возврат, см. [`YandexPayApi::refund_order`].
+    ///
+    /// Возврат асинхронный: статус заказа может измениться не сразу, результат операции
+    /// можно дождаться через [`YandexPayApi::wait_for_operation`].
+    pub async fn refund(self, request: RefundRequest) -> R<OrderStage<'a, C>> {
+        self.api.refund_order(&*self.order_id, request).await?;
+        self.refresh().await
+    }
+}
+
+impl<'a, C: HttpClient> OrderHandle<'a, C, Confirmed> {
+    /// Подтверждает оплату Сплита, см. [`YandexPayApi::submit_order`]
+    pub async fn submit(self, request: SubmitRequest) -> R<OrderStage<'a, C>> {
+        self.api.submit_order(&*self.order_id, request).await?;
+        self.refresh().await
+    }
+}
+
+impl<C: HttpClient> YandexPayApi<C> {
+    /// Запрашивает заказ и возвращает его вместе со статусом оплаты на уровне типов
+    pub async fn order_stage(&self, order_id: impl Into<String>) -> R<OrderStage<'_, C>> {
+        let order_id = order_id.into();
+        let data = self.get_order(&*order_id).await?;
+        Ok(OrderStage::new(self, order_id, data))
+    }
+
+    /// Создаёт заказ и запрашивает его.
+    ///
+    /// Возвращает ответ `create_order` со ссылкой на оплату и текущий статус заказа.
+    pub async fn create_order_stage(
+        &self,
+        request: CreateOrderRequest,
+    ) -> R<(CreateOrderResponse, OrderStage<'_, C>)> {
+        let order_id = request.order_id.clone();
+        let response = self.create_order(request).await?;
+        Ok((response, self.order_stage(order_id).await?))
+    }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use super::*;
+
+    fn create_order(order_id: &str) -> CreateOrderRequest {
+        let cart = RenderedCart::new()
+            .items(vec![
+                RenderedCartItem::new()
+                    .product_id("p1")
+                    .quantity(ItemQuantity::new().count(2.0).available(2.0).build())
+                    .title("Товар")
+                    .total(Money::from_rubles(200))
+                    .build(),
+            ])
+            .total(CartTotal::new().amount(Money::from_rubles(200)).build())
+            .external_id("cart")
+            .build();
+        CreateOrderRequest::new()
+            .cart(cart)
+            .order_id(order_id)
+            .build()
+    }
+
+    fn api(fake: &FakeYandexPay) -> YandexPayApi<FakeYandexPay> {
+        YandexPayApi::new("http://localhost".into(), "key".into(), fake.clone())
+    }
+
+    #[tokio::test]
+    async fn test_capture_and_refund() {
+        let fake = FakeYandexPay::new();
+        let api = api(&fake);
+        let (response, stage) = api
+            .create_order_stage(create_order("order-1"))
+            .await
+            .unwrap();
+        assert!(!response.payment_url.is_empty());
+        assert!(matches!(stage, OrderStage::Pending(_)));
+
+        fake.set_payment_status("order-1", PaymentStatus::Authorized);
+        let OrderStage::Authorized(order) = api.order_stage("order-1").await.unwrap() else {
+            panic!("order must be authorized");
+        };
+        let OrderStage::Captured(order) = order
+            .capture(CaptureOrderRequest::new().build())
+            .await
+            .unwrap()
+        else {
+            panic!("order must be captured");
+        };
+        let stage = order
+            .refund(
+                RefundRequest::new()
+                    .refund_amount(Money::from_rubles(50))
+                    .external_operation_id(Some("refund-1".into()))
+                    .build(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(stage.payment_status(), Some(&PaymentStatus::Captured));
+        fake.set_operation_status("refund-1", OperationStatus::Success);
+        let stage = api.order_stage("order-1").await.unwrap();
+        assert_eq!(
+            stage.payment_status(),
+            Some(&PaymentStatus::PartiallyRefunded)
+        );
+        let OrderStage::PartiallyRefunded(order) = stage else {
+            panic!("order must be partially refunded");
+        };
+        order
+            .refund(
+                RefundRequest::new()
+                    .refund_amount(Money::from_rubles(150))
+                    .external_operation_id(Some("refund-2".into()))
+                    .build(),
+            )
+            .await
+            .unwrap();
+        fake.set_operation_status("refund-2", OperationStatus::Success);
+        let stage = api.order_stage("order-1").await.unwrap();
+        assert!(matches!(stage, OrderStage::Refunded(_)));
+    }
+
+    #[tokio::test]
+    async fn test_cancel_submit_and_rollback() {
+        let fake = FakeYandexPay::new();
+        let api = api(&fake);
+        api.create_order(create_order("order-1")).await.unwrap();
+        fake.set_payment_status("order-1", PaymentStatus::Authorized);
+        let OrderStage::Authorized(order) = api.order_stage("order-1").await.unwrap() else {
+            panic!("order must be authorized");
+        };
+        let stage = order
+            .cancel(CancelOrderRequest::new().reason("test").build())
+            .await
+            .unwrap();
+        assert!(matches!(stage, OrderStage::Voided(_)));
+
+        api.create_order(create_order("order-2")).await.unwrap();
+        fake.set_payment_status("order-2", PaymentStatus::Confirmed);
+        let OrderStage::Confirmed(order) = api.order_stage("order-2").await.unwrap() else {
+            panic!("order must be confirmed");
+        };
+        let stage = order
+            .submit(
+                SubmitRequest::new()
+                    .external_operation_id("submit-1")
+                    .build(),
+            )
+            .await
+            .unwrap();
+        let OrderStage::Captured(order) = stage else {
+            panic!("order must be captured");
+        };
+        assert_eq!(order.order_id(), "order-2");
+        let stage = order.rollback().await.unwrap();
+        assert!(matches!(stage, OrderStage::Failed(_)));
+    }
+
+    #[tokio::test]
+    async fn test_unexpected_status() {
+        let fake = FakeYandexPay::new();
+        let api = api(&fake);
+        api.create_order(create_order("order-1")).await.unwrap();
+        let mut data = api.get_order("order-1").await.unwrap();
+        data.order.as_mut().unwrap().payment_status =
+            Some(PaymentStatus::Unknown("ON_HOLD".into()));
+        let stage = OrderStage::new(&api, "order-1".into(), data.clone());
+        assert!(matches!(stage, OrderStage::Unexpected(_)));
+        data.order.as_mut().unwrap().payment_status = None;
+        let stage = OrderStage::new(&api, "order-1".into(), data);
+        let OrderStage::Unexpected(order) = stage else {
+            panic!("order without status must be unexpected");
+        };
+        assert!(matches!(
+            order.refresh().await.unwrap(),
+            OrderStage::Pending(_)
+        ));
+    }
+}
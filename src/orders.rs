@@ -1,8 +1,7 @@
+use crate::Money;
 use crate::serde_help::*;
-use crate::*;
 use builder_pattern::Builder;
 use serde::{Deserialize, Serialize};
-use serde_repr::{Deserialize_repr, Serialize_repr};
 
 #[derive(Debug, Clone, Serialize, Deserialize, Builder)]
 #[serde(rename_all = "camelCase")]
@@ -113,41 +112,36 @@ pub struct RenderedCartItem {
     // Наименование товара. Max length: 2048
     pub title: String,
     #[into]
-    #[serde(with = "string_as_float")]
     /// Суммарная цена за позицию с учётом скидок на позицию. Example: 123.45
-    pub total: f64,
+    pub total: Money,
     #[default(None)]
     #[serde(skip_serializing_if = "Option::is_none")]
     /// Описание товара. Max length: 2048
     pub description: Option<String>,
     #[default(None)]
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[serde(with = "option_string_as_float")]
     /// Цена за единицу товара с учётом скидок на позицию. Example: 123.45
-    pub discounted_unit_price: Option<f64>,
+    pub discounted_unit_price: Option<Money>,
     #[default(None)]
     #[serde(skip_serializing_if = "Option::is_none")]
     /// Промо параметры товара
     pub features: Option<CartItemFeatures>,
     #[default(None)]
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[serde(with = "option_string_as_float")]
     /// Количество баллов Плюса. Поле только для чтения. Example: 123.45
-    pub points_amount: Option<f64>,
+    pub points_amount: Option<Money>,
     #[default(None)]
     #[serde(skip_serializing_if = "Option::is_none")]
     /// Данные для формирования чека
     pub receipt: Option<ItemReceipt>,
     #[default(None)]
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[serde(with = "option_string_as_float")]
     /// Суммарная цена за позицию без учета скидок. Example: 123.45
-    pub subtotal: Option<f64>,
+    pub subtotal: Option<Money>,
     #[default(None)]
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[serde(with = "option_string_as_float")]
     /// Полная цена за единицу товара без учетка скидки. Example: 123.45
-    pub unit_price: Option<f64>,
+    pub unit_price: Option<Money>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Builder)]
@@ -155,15 +149,19 @@ pub struct RenderedCartItem {
 /// <https://pay.yandex.ru/docs/ru/custom/backend/yandex-pay-api/order/merchant_v1_orders-post#carttotal>
 pub struct CartTotal {
     #[into]
-    #[serde(with = "string_as_float")]
-    pub amount: f64,
+    pub amount: Money,
     #[default(None)]
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[serde(with = "option_string_as_float")]
     /// Количество баллов Плюса
     /// Поле только для чтения. Переданные значения будут проигнорированы.
     /// Example: 123.45
-    pub points_amount: Option<f64>,
+    pub points_amount: Option<Money>,
+}
+
+impl Default for CartTotal {
+    fn default() -> Self {
+        CartTotal::new().amount(Money::ZERO).build()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Builder)]
@@ -208,14 +206,12 @@ pub struct SMSOffer {
 pub struct CustomerAggregates {
     #[default(None)]
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[serde(with = "option_string_as_float")]
     /// Сумма первого успешного заказа
-    pub amount_first_successful_order: Option<f64>,
+    pub amount_first_successful_order: Option<Money>,
     #[default(None)]
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[serde(with = "option_string_as_float")]
     /// Сумма последнего успешного заказа
-    pub amount_latest_successful_order: Option<f64>,
+    pub amount_latest_successful_order: Option<Money>,
     #[default(None)]
     #[serde(skip_serializing_if = "Option::is_none")]
     /// Куки. Max length: 2048
@@ -294,31 +290,26 @@ pub struct PeriodCheckAggregates {
     #[serde(skip_serializing_if = "Option::is_none")]
     /// Количество успешных заказов за последние 12 месяцев
     pub successful_orders_count_twelve_months: Option<i32>,
-    #[serde(with = "option_string_as_float")]
     #[default(None)]
     #[serde(skip_serializing_if = "Option::is_none")]
     /// Сумма успешных заказов за последние 9 месяцев. Example: 123.45
-    pub total_amount_successful_orders_nine_months: Option<f64>,
-    #[serde(with = "option_string_as_float")]
+    pub total_amount_successful_orders_nine_months: Option<Money>,
     #[default(None)]
     #[serde(skip_serializing_if = "Option::is_none")]
     /// Сумма успешных заказов за последний месяц. Example: 123.45
-    pub total_amount_successful_orders_one_month: Option<f64>,
-    #[serde(with = "option_string_as_float")]
+    pub total_amount_successful_orders_one_month: Option<Money>,
     #[default(None)]
     #[serde(skip_serializing_if = "Option::is_none")]
     /// Сумма успешных заказов за последние 6 месяцев. Example: 123.45
-    pub total_amount_successful_orders_six_months: Option<f64>,
-    #[serde(with = "option_string_as_float")]
+    pub total_amount_successful_orders_six_months: Option<Money>,
     #[default(None)]
     #[serde(skip_serializing_if = "Option::is_none")]
     /// Сумма успешных заказов за последние 3 месяца. Example: 123.45
-    pub total_amount_successful_orders_three_months: Option<f64>,
-    #[serde(with = "option_string_as_float")]
+    pub total_amount_successful_orders_three_months: Option<Money>,
     #[default(None)]
     #[serde(skip_serializing_if = "Option::is_none")]
     /// Сумма успешных заказов за последние 12 месяцев. Example: 123.45
-    pub total_amount_successful_orders_twelve_months: Option<f64>,
+    pub total_amount_successful_orders_twelve_months: Option<Money>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Builder)]
@@ -329,7 +320,7 @@ pub struct ItemQuantity {
     #[into]
     /// Количество товара в заказе
     pub count: f64,
-    #[serde(with = "string_as_float")]
+    #[serde(default, with = "string_as_float")]
     #[into]
     /// Максимально доступное количество товара
     pub available: f64,
@@ -352,11 +343,10 @@ pub struct ItemReceipt {
     #[default(None)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub agent: Option<Agent>,
-    #[serde(with = "option_string_as_float")]
     #[default(None)]
     #[serde(skip_serializing_if = "Option::is_none")]
     /// Не должно содержать больше двух знаков после запятой. Например: 1.12, 5.1, 10, 11.00 .
-    pub excise: Option<f64>,
+    pub excise: Option<Money>,
     #[default(None)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub mark_quantity: Option<MarkQuantity>,
@@ -538,194 +528,193 @@ pub struct UniqrMetadata {
     pub uni_qr_id: String,
 }
 
-#[derive(Debug, Clone, Serialize_repr, Deserialize_repr, PartialEq, Eq, Hash)]
-#[repr(u8)]
-/// Налог <https://pay.yandex.ru/docs/ru/custom/fns#tax>
-pub enum Tax {
-    /// НДС по ставке 20%
-    Vat20 = 1,
-    /// НДС по ставке 10%
-    Vat10 = 2,
-    /// НДС по расчетной ставке 20/120
-    Vat20_120 = 3,
-    /// НДС по расчетной ставке 10/110
-    Vat10_110 = 4,
-    /// НДС по ставке 0%
-    Vat0 = 5,
-    /// Без НДС
-    NoVat = 6,
-    /// НДС по ставке 7%
-    Vat7 = 7,
-    /// НДС по ставке 5%
-    Vat5 = 8,
-    /// НДС по расчетной ставке 7/107   
-    Vat7_107 = 9,
-    /// НДС по расчетной ставке 5/105
-    Vat5_105 = 10,
-}
-
-#[derive(Debug, Clone, Serialize_repr, Deserialize_repr, PartialEq, Eq, Hash)]
-#[repr(u8)]
-/// Значения paymentMethodType - «признак способа расчета» (тег 1214)
-/// <https://pay.yandex.ru/docs/ru/custom/fns#payment-method-type>
-pub enum PaymentMethodType {
-    /// Полная предварительная оплата до момента передачи предмета расчета
-    FullPrepayment = 1,
-    /// Частичная предварительная оплата до момента передачи предмета расчета
-    PartialPrepayment = 2,
-    /// Аванс
-    Advance = 3,
-    /// Полная оплата в момент передачи предмета расчета
-    FullPayment = 4,
-    /// Частичная оплата предмета расчета в момент его передачи с последующей оплатой в кредит
-    PartialPayment = 5,
-    /// Передача предмета расчета без его оплаты в момент его передачи с последующей оплатой в кредит
-    CreditTransfer = 6,
-    /// Оплата предмета расчета после его передачи с оплатой в кредит
-    CreditPayment = 7,
-}
-
-#[derive(Debug, Clone, Serialize_repr, Deserialize_repr, PartialEq, Eq, Hash)]
-#[repr(u8)]
-/// Значения paymentSubjectType - «признак предмета расчета» (тег 1212)
-/// <https://pay.yandex.ru/docs/ru/custom/fns#payment-subject-type>
-pub enum PaymentSubjectType {
-    /// Товар
-    Goods = 1,
-    /// Подакцизный товар
-    ExciseGoods = 2,
-    /// Работа
-    Work = 3,
-    /// Услуга
-    Service = 4,
-    /// Ставка азартной игры
-    GamblingBet = 5,
-    /// Выигрыш азартной игры
-    GamblingWin = 6,
-    /// Лотерейный билет
-    LotteryTicket = 7,
-    /// Выигрыш лотереи
-    LotteryWin = 8,
-    /// Предоставление РИД
-    IntellectualProperty = 9,
-    /// Платеж
-    Payment = 10,
-    /// Агентское вознаграждение
-    AgencyFee = 11,
-    /// Составной предмет расчета
-    Composite = 12,
-    /// Иной предмет расчета
-    Other = 13,
-    /// Имущественное право
-    PropertyRight = 14,
-    /// Внереализационныи доход
-    NonOperatingIncome = 15,
-    /// Страховые взносы: о суммах расходов, уменьшающих сумму налога (авансовых платежей) в соответствии с п. 3.1 статьи 346.21 НК РФ
-    InsuranceContributionsTaxReduction = 16,
-    /// Торговый сбор
-    TradeFee = 17,
-    /// Курортный сбор
-    ResortFee = 18,
-    /// Залог
-    Deposit = 19,
-    /// Расход: о суммах произведенных расходов в соответствии со статьей 346.16 НК РФ, уменьшающих доход
-    ExpenseTaxReduction = 20,
-    /// Взносы на обязательное пенсионное страхование ИП
-    PensionInsuranceIP = 21,
-    /// Взносы на обязательное пенсионное страхование
-    PensionInsurance = 22,
-    /// Взносы на обязательное медицинское страхование ИП
-    MedicalInsuranceIP = 23,
-    /// Взносы на обязательное медицинское страхование
-    MedicalInsurance = 24,
-    /// Взносы на обязательное социальное страхование
-    SocialInsurance = 25,
-    /// Платеж казино
-    CasinoPayment = 26,
-}
-
-#[derive(Debug, Clone, Serialize_repr, Deserialize_repr, PartialEq, Eq, Hash)]
-#[repr(u8)]
-/// Значения quantity.measure - «мера количества предмета расчета» (тег 2108)
-/// <https://pay.yandex.ru/docs/ru/custom/fns#measure-code>
-pub enum Measure {
-    /// Штуки или единицы
-    Units = 0,
-    /// Грамм
-    Gram = 10,
-    /// Килограмм
-    Kilogram = 11,
-    /// Тонна
-    Tonne = 12,
-    /// Сантиметр
-    Centimeter = 20,
-    /// Дециметр
-    Decimeter = 21,
-    /// Метр
-    Meter = 22,
-    /// Квадратный сантиметр
-    SquareCentimeter = 30,
-    /// Квадратный дециметр
-    SquareDecimeter = 31,
-    /// Квадратный метр
-    SquareMeter = 32,
-    /// Миллилитр
-    Milliliter = 40,
-    /// Литр
-    Liter = 41,
-    /// Кубический метр
-    CubicMeter = 42,
-    /// Киловатт час
-    KilowattHour = 50,
-    /// Гигакалория
-    Gigacalorie = 51,
-    /// Сутки (день)
-    Day = 70,
-    /// Час
-    Hour = 71,
-    /// Минута
-    Minute = 72,
-    /// Секунда
-    Second = 73,
-    /// Килобайт
-    Kilobyte = 80,
-    /// Мегабайт
-    Megabyte = 81,
-    /// Гигабайт
-    Gigabyte = 82,
-    /// Терабайт
-    Terabyte = 83,
-    /// Применяется при использовании иных мер измерения
-    Other = 255,
-}
-
-#[derive(Debug, Clone, Serialize_repr, Deserialize_repr, PartialEq, Eq, Hash)]
-#[repr(u8)]
-/// Признак агента по предмету расчета (тег 1222)
-/// <https://pay.yandex.ru/docs/ru/custom/fns#agent-type>
-pub enum AgentType {
-    /// Банковский платежный агент
-    BankPaymentAgent = 1,
-    /// Банковский платежный субагент
-    BankPaymentSubagent = 2,
-    /// Платежный агент
-    PaymentAgent = 3,
-    /// Платежный субагент
-    PaymentSubagent = 4,
-    /// Поверенный
-    Attorney = 5,
-    /// Комиссионер
-    Commissioner = 6,
-    /// Иной агент
-    OtherAgent = 7,
+int_enum! {
+    /// Налог <https://pay.yandex.ru/docs/ru/custom/fns#tax>
+    pub enum Tax {
+        /// НДС по ставке 20%
+        Vat20 = 1,
+        /// НДС по ставке 10%
+        Vat10 = 2,
+        /// НДС по расчетной ставке 20/120
+        Vat20_120 = 3,
+        /// НДС по расчетной ставке 10/110
+        Vat10_110 = 4,
+        /// НДС по ставке 0%
+        Vat0 = 5,
+        /// Без НДС
+        NoVat = 6,
+        /// НДС по ставке 7%
+        Vat7 = 7,
+        /// НДС по ставке 5%
+        Vat5 = 8,
+        /// НДС по расчетной ставке 7/107
+        Vat7_107 = 9,
+        /// НДС по расчетной ставке 5/105
+        Vat5_105 = 10,
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
-pub enum ShippingType {
-    #[serde(rename = "COURIER")]
-    Courier,
-    #[serde(rename = "PICKUP")]
-    Pickup,
+int_enum! {
+    /// Значения paymentMethodType - «признак способа расчета» (тег 1214)
+    /// <https://pay.yandex.ru/docs/ru/custom/fns#payment-method-type>
+    pub enum PaymentMethodType {
+        /// Полная предварительная оплата до момента передачи предмета расчета
+        FullPrepayment = 1,
+        /// Частичная предварительная оплата до момента передачи предмета расчета
+        PartialPrepayment = 2,
+        /// Аванс
+        Advance = 3,
+        /// Полная оплата в момент передачи предмета расчета
+        FullPayment = 4,
+        /// Частичная оплата предмета расчета в момент его передачи с последующей оплатой в кредит
+        PartialPayment = 5,
+        /// Передача предмета расчета без его оплаты в момент его передачи с последующей оплатой в кредит
+        CreditTransfer = 6,
+        /// Оплата предмета расчета после его передачи с оплатой в кредит
+        CreditPayment = 7,
+    }
+}
+
+int_enum! {
+    /// Значения paymentSubjectType - «признак предмета расчета» (тег 1212)
+    /// <https://pay.yandex.ru/docs/ru/custom/fns#payment-subject-type>
+    pub enum PaymentSubjectType {
+        /// Товар
+        Goods = 1,
+        /// Подакцизный товар
+        ExciseGoods = 2,
+        /// Работа
+        Work = 3,
+        /// Услуга
+        Service = 4,
+        /// Ставка азартной игры
+        GamblingBet = 5,
+        /// Выигрыш азартной игры
+        GamblingWin = 6,
+        /// Лотерейный билет
+        LotteryTicket = 7,
+        /// Выигрыш лотереи
+        LotteryWin = 8,
+        /// Предоставление РИД
+        IntellectualProperty = 9,
+        /// Платеж
+        Payment = 10,
+        /// Агентское вознаграждение
+        AgencyFee = 11,
+        /// Составной предмет расчета
+        Composite = 12,
+        /// Иной предмет расчета
+        Other = 13,
+        /// Имущественное право
+        PropertyRight = 14,
+        /// Внереализационныи доход
+        NonOperatingIncome = 15,
+        /// Страховые взносы: о суммах расходов, уменьшающих сумму налога (авансовых платежей) в соответствии с п. 3.1 статьи 346.21 НК РФ
+        InsuranceContributionsTaxReduction = 16,
+        /// Торговый сбор
+        TradeFee = 17,
+        /// Курортный сбор
+        ResortFee = 18,
+        /// Залог
+        Deposit = 19,
+        /// Расход: о суммах произведенных расходов в соответствии со статьей 346.16 НК РФ, уменьшающих доход
+        ExpenseTaxReduction = 20,
+        /// Взносы на обязательное пенсионное страхование ИП
+        PensionInsuranceIP = 21,
+        /// Взносы на обязательное пенсионное страхование
+        PensionInsurance = 22,
+        /// Взносы на обязательное медицинское страхование ИП
+        MedicalInsuranceIP = 23,
+        /// Взносы на обязательное медицинское страхование
+        MedicalInsurance = 24,
+        /// Взносы на обязательное социальное страхование
+        SocialInsurance = 25,
+        /// Платеж казино
+        CasinoPayment = 26,
+    }
+}
+
+int_enum! {
+    /// Значения quantity.measure - «мера количества предмета расчета» (тег 2108)
+    /// <https://pay.yandex.ru/docs/ru/custom/fns#measure-code>
+    pub enum Measure {
+        /// Штуки или единицы
+        Units = 0,
+        /// Грамм
+        Gram = 10,
+        /// Килограмм
+        Kilogram = 11,
+        /// Тонна
+        Tonne = 12,
+        /// Сантиметр
+        Centimeter = 20,
+        /// Дециметр
+        Decimeter = 21,
+        /// Метр
+        Meter = 22,
+        /// Квадратный сантиметр
+        SquareCentimeter = 30,
+        /// Квадратный дециметр
+        SquareDecimeter = 31,
+        /// Квадратный метр
+        SquareMeter = 32,
+        /// Миллилитр
+        Milliliter = 40,
+        /// Литр
+        Liter = 41,
+        /// Кубический метр
+        CubicMeter = 42,
+        /// Киловатт час
+        KilowattHour = 50,
+        /// Гигакалория
+        Gigacalorie = 51,
+        /// Сутки (день)
+        Day = 70,
+        /// Час
+        Hour = 71,
+        /// Минута
+        Minute = 72,
+        /// Секунда
+        Second = 73,
+        /// Килобайт
+        Kilobyte = 80,
+        /// Мегабайт
+        Megabyte = 81,
+        /// Гигабайт
+        Gigabyte = 82,
+        /// Терабайт
+        Terabyte = 83,
+        /// Применяется при использовании иных мер измерения
+        Other = 255,
+    }
+}
+
+int_enum! {
+    /// Признак агента по предмету расчета (тег 1222)
+    /// <https://pay.yandex.ru/docs/ru/custom/fns#agent-type>
+    pub enum AgentType {
+        /// Банковский платежный агент
+        BankPaymentAgent = 1,
+        /// Банковский платежный субагент
+        BankPaymentSubagent = 2,
+        /// Платежный агент
+        PaymentAgent = 3,
+        /// Платежный субагент
+        PaymentSubagent = 4,
+        /// Поверенный
+        Attorney = 5,
+        /// Комиссионер
+        Commissioner = 6,
+        /// Иной агент
+        OtherAgent = 7,
+    }
+}
+
+string_enum! {
+    pub enum ShippingType {
+        Courier = "COURIER",
+        Pickup = "PICKUP",
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
@@ -755,21 +744,21 @@ pub enum OrderSource {
     CmsPlugin,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
-pub enum AvailablePaymentMethod {
-    #[serde(rename = "CARD")]
-    Card,
-    #[serde(rename = "SPLIT")]
-    Split,
+string_enum! {
+    pub enum AvailablePaymentMethod {
+        Card = "CARD",
+        Split = "SPLIT",
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq, Hash)]
-pub enum CurrencyCode {
-    #[default]
-    #[serde(rename = "RUB")]
-    /// Russian Ruble
-    /// ISO 4217 code: RUB
-    Rub,
+string_enum! {
+    #[derive(Default)]
+    pub enum CurrencyCode {
+        #[default]
+        /// Russian Ruble
+        /// ISO 4217 code: RUB
+        Rub = "RUB",
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -937,7 +926,7 @@ mod tests {
                 RenderedCart::new()
                     .items(vec![])
                     .external_id("123")
-                    .total(CartTotal::new().amount(100.0).build())
+                    .total(CartTotal::new().amount(Money::from_rubles(100)).build())
                     .build(),
             )
             .order_id("123")
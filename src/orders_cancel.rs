@@ -25,14 +25,14 @@ pub struct OperationResponseData {
 /// <https://pay.yandex.ru/docs/ru/custom/backend/yandex-pay-api/order/merchant_v1_cancel-post#operation>
 pub struct Operation {
     /// Сумма операции в фиатной валюте
-    pub amount: f64,
+    pub amount: Money,
     /// Уникальный идентификатор операции
     pub operation_id: String,
     /// Тип операции
     pub operation_type: OperationType,
     /// Идентификатор заказа
     pub order_id: String,
-    #[serde(with = "option_iso8601")]
+    #[serde(default, with = "option_iso8601")]
     /// Дата и время создания операции
     pub created: Option<Time>,
     /// Идентификатор операции на стороне продавца
@@ -43,7 +43,7 @@ pub struct Operation {
     pub reason: Option<String>,
     /// Статус операции
     pub status: OperationStatus,
-    #[serde(with = "option_iso8601")]
+    #[serde(default, with = "option_iso8601")]
     /// Дата и время обновления операции
     pub updated: Option<Time>,
 }
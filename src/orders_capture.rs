@@ -1,6 +1,6 @@
+use crate::serde_help::string_enum;
 use crate::{
     orders_id::{CartItemType, Coupon, Discount, Measurements, ShippingMethodType},
-    serde_help::*,
     *,
 };
 use builder_pattern::Builder;
@@ -19,10 +19,9 @@ pub struct CaptureOrderRequest {
     /// Идентификатор операции
     pub external_operation_id: Option<String>,
     #[default(None)]
-    #[serde(with = "option_string_as_float")]
     #[serde(skip_serializing_if = "Option::is_none")]
     /// Сумма к списанию. Если не указана, будет списана итоговая стоимость переданной корзины
-    pub order_amount: Option<f64>,
+    pub order_amount: Option<Money>,
     #[default(None)]
     #[serde(skip_serializing_if = "Option::is_none")]
     /// Итоговый способ доставки
@@ -33,8 +32,7 @@ pub struct CaptureOrderRequest {
 #[serde(rename_all = "camelCase")]
 /// <https://pay.yandex.ru/docs/ru/custom/backend/yandex-pay-api/order/merchant_v1_capture-post#shippingprice>
 pub struct ShippingPrice {
-    #[serde(with = "string_as_float")]
-    pub amount: f64,
+    pub amount: Money,
     pub method_type: ShippingMethodType,
 }
 
@@ -72,14 +70,14 @@ pub struct CartWithRequiredTotalWithoutFinalPrice {
     pub version: CartVersion,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-pub enum CartVersion {
-    #[default]
-    #[serde(rename = "VALID")]
-    /// Версия корзины по умолчанию
-    Valid,
-    #[serde(rename = "SEMIVALID")]
-    SemiValid,
+string_enum! {
+    #[derive(Default)]
+    pub enum CartVersion {
+        #[default]
+        /// Версия корзины по умолчанию
+        Valid = "VALID",
+        SemiValid = "SEMIVALID",
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Builder)]
@@ -97,9 +95,8 @@ pub struct CartItemWithoutFinalPriceCamelCase {
     pub description: Option<String>,
     /// Цена за единицу товара с учётом скидок на позицию
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[serde(with = "option_string_as_float")]
     #[default(None)]
-    pub discounted_unit_price: Option<f64>,
+    pub discounted_unit_price: Option<Money>,
     /// Промо параметры товара
     #[serde(skip_serializing_if = "Option::is_none")]
     #[default(None)]
@@ -110,18 +107,16 @@ pub struct CartItemWithoutFinalPriceCamelCase {
     pub measurements: Option<Measurements>,
     /// Количество баллов Плюса
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[serde(with = "option_string_as_float")]
     #[default(None)]
-    pub points_amount: Option<f64>,
+    pub points_amount: Option<Money>,
     /// Данные для формирования чека
     #[serde(skip_serializing_if = "Option::is_none")]
     #[default(None)]
     pub receipt: Option<ItemReceipt>,
     /// Суммарная цена за позицию без учета скидок
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[serde(with = "option_string_as_float")]
     #[default(None)]
-    pub subtotal: Option<f64>,
+    pub subtotal: Option<Money>,
     /// Наименование товара
     #[serde(skip_serializing_if = "Option::is_none")]
     #[default(None)]
@@ -132,7 +127,6 @@ pub struct CartItemWithoutFinalPriceCamelCase {
     pub item_type: Option<CartItemType>,
     /// Полная цена за единицу товара без учёта скидки
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[serde(with = "option_string_as_float")]
     #[default(None)]
-    pub unit_price: Option<f64>,
+    pub unit_price: Option<Money>,
 }
@@ -19,33 +19,31 @@ pub struct OrderResponseData {
 /// Представляет данные о доставке.
 /// <https://pay.yandex.ru/docs/ru/custom/backend/yandex-pay-api/order/merchant_v1_order-get#delivery>
 pub struct Delivery {
-    #[serde(with = "string_as_float")]
-    pub price: f64,
-    #[serde(with = "option_string_as_float")]
-    pub actual_price: Option<f64>,
-    #[serde(with = "option_iso8601")]
+    pub price: Money,
+    pub actual_price: Option<Money>,
+    #[serde(default, with = "option_iso8601")]
     pub created: Option<Time>,
     pub status: DeliveryStatus,
-    #[serde(with = "option_iso8601")]
+    #[serde(default, with = "option_iso8601")]
     pub updated: Option<Time>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
-/// Возможные статусы доставки.
-pub enum DeliveryStatus {
-    New,
-    Estimating,
-    Expired,
-    ReadyForApproval,
-    Collecting,
-    Preparing,
-    Delivering,
-    Delivered,
-    Returning,
-    Returned,
-    Failed,
-    Cancelled,
+string_enum! {
+    /// Возможные статусы доставки.
+    pub enum DeliveryStatus {
+        New = "NEW",
+        Estimating = "ESTIMATING",
+        Expired = "EXPIRED",
+        ReadyForApproval = "READY_FOR_APPROVAL",
+        Collecting = "COLLECTING",
+        Preparing = "PREPARING",
+        Delivering = "DELIVERING",
+        Delivered = "DELIVERED",
+        Returning = "RETURNING",
+        Returned = "RETURNED",
+        Failed = "FAILED",
+        Cancelled = "CANCELLED",
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -53,8 +51,7 @@ pub enum DeliveryStatus {
 /// Представляет данные операции в ответе на запрос деталей заказа.
 /// <https://pay.yandex.ru/docs/ru/custom/backend/yandex-pay-api/order/merchant_v1_order-get#orderresponseoperation>
 pub struct OrderResponseOperation {
-    #[serde(with = "string_as_float")]
-    pub amount: f64,
+    pub amount: Money,
     #[serde(rename = "operationId")]
     pub operation_id: String,
     #[serde(rename = "operationType")]
@@ -62,37 +59,37 @@ pub struct OrderResponseOperation {
     #[serde(rename = "orderId")]
     pub order_id: String,
     pub approval_code: Option<String>,
-    #[serde(with = "option_iso8601")]
+    #[serde(default, with = "option_iso8601")]
     pub created: Option<Time>,
     pub external_operation_id: Option<String>,
     pub params: Option<serde_json::Value>,
     pub reason: Option<String>,
     pub status: OperationStatus,
-    #[serde(with = "option_iso8601")]
+    #[serde(default, with = "option_iso8601")]
     pub updated: Option<Time>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
-/// Возможные типы операций.
-pub enum OperationType {
-    Authorize,
-    BindCard,
-    Refund,
-    Capture,
-    Void,
-    Recurring,
-    Prepayment,
-    Submit,
-}
-
-#[derive(Debug, Serialize, Clone, Deserialize)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
-/// Возможные статусы операции.
-pub enum OperationStatus {
-    Pending,
-    Success,
-    Fail,
+string_enum! {
+    /// Возможные типы операций.
+    pub enum OperationType {
+        Authorize = "AUTHORIZE",
+        BindCard = "BIND_CARD",
+        Refund = "REFUND",
+        Capture = "CAPTURE",
+        Void = "VOID",
+        Recurring = "RECURRING",
+        Prepayment = "PREPAYMENT",
+        Submit = "SUBMIT",
+    }
+}
+
+string_enum! {
+    /// Возможные статусы операции.
+    pub enum OperationStatus {
+        Pending = "PENDING",
+        Success = "SUCCESS",
+        Fail = "FAIL",
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -109,7 +106,7 @@ pub struct BaseMerchantApiOrder {
     pub currency_code: CurrencyCode,
 
     /// Дата и время создания заказа (ISO 8601)
-    #[serde(with = "option_iso8601")]
+    #[serde(default, with = "option_iso8601")]
     pub created: Option<Time>,
     #[serde(default)]
     /// Флаг, идет ли заказ по флоу "отложенной оплаты"
@@ -126,8 +123,7 @@ pub struct BaseMerchantApiOrder {
 
     /// Полная стоимость заказа к оплате с учётом возвратов, доставки, скидок и промокодов
     /// Пример: `123.45`
-    #[serde(with = "string_as_float")]
-    pub order_amount: f64,
+    pub order_amount: Money,
 
     /// Id существующего заказа на стороне продавца, переданный при инициализации кнопки
     /// Максимальная длина: 2048
@@ -152,22 +148,22 @@ pub struct BaseMerchantApiOrder {
     pub shipping_method: Option<ShippingMethod>,
 
     /// Дата и время обновления заказа (ISO 8601)
-    #[serde(with = "option_iso8601")]
+    #[serde(default, with = "option_iso8601")]
     pub updated: Option<Time>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
-/// Возможные статусы оплаты.
-pub enum PaymentStatus {
-    Pending,
-    Authorized,
-    Captured,
-    Voided,
-    Refunded,
-    Confirmed,
-    PartiallyRefunded,
-    Failed,
+string_enum! {
+    /// Возможные статусы оплаты.
+    pub enum PaymentStatus {
+        Pending = "PENDING",
+        Authorized = "AUTHORIZED",
+        Captured = "CAPTURED",
+        Voided = "VOIDED",
+        Refunded = "REFUNDED",
+        Confirmed = "CONFIRMED",
+        PartiallyRefunded = "PARTIALLY_REFUNDED",
+        Failed = "FAILED",
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -178,6 +174,7 @@ pub struct Cart {
     /// Позиции корзины
     pub items: Vec<CartItem>,
 
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     /// Внутренний идентификатор корзины Яндекс Пэй.
     /// Максимальная длина: 2048
     pub cart_id: String,
@@ -202,7 +199,7 @@ pub struct Cart {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[serde(rename_all = "camelCase")]
 /// Представляет данные о выбранном способе оплаты.
 /// <https://pay.yandex.ru/docs/ru/custom/backend/yandex-pay-api/order/merchant_v1_order-get#paymentmethod>
 pub struct PaymentMethod {
@@ -217,39 +214,40 @@ pub struct PaymentMethod {
     pub card_network: Option<CardNetwork>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
-/// Возможные типы способов оплаты.
-pub enum MethodType {
-    Card,
-    Split,
-    Sbp,
-    SplitSbp,
-    CashOnDelivery,
-    CardOnDelivery,
+string_enum! {
+    /// Возможные типы способов оплаты.
+    pub enum MethodType {
+        Card = "CARD",
+        Split = "SPLIT",
+        Sbp = "SBP",
+        SplitSbp = "SPLIT_SBP",
+        CashOnDelivery = "CASH_ON_DELIVERY",
+        CardOnDelivery = "CARD_ON_DELIVERY",
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
-/// Возможные платежные системы.
-pub enum CardNetwork {
-    Amex,
-    Discover,
-    Jcb,
-    Mastercard,
-    Maestro,
-    Visaelectron,
-    Visa,
-    Mir,
-    Unionpay,
-    Uzcard,
-    Humocard,
-    Unknown,
-    Undefined,
+string_enum! {
+    /// Возможные платежные системы.
+    ///
+    /// Значение `UNKNOWN` из API попадает в [`CardNetwork::Unknown`].
+    pub enum CardNetwork {
+        Amex = "AMEX",
+        Discover = "DISCOVER",
+        Jcb = "JCB",
+        Mastercard = "MASTERCARD",
+        Maestro = "MAESTRO",
+        Visaelectron = "VISAELECTRON",
+        Visa = "VISA",
+        Mir = "MIR",
+        Unionpay = "UNIONPAY",
+        Uzcard = "UZCARD",
+        Humocard = "HUMOCARD",
+        Undefined = "UNDEFINED",
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[serde(rename_all = "camelCase")]
 /// Представляет данные о выбранном способе доставки.
 /// <https://pay.yandex.ru/docs/ru/custom/backend/yandex-pay-api/order/merchant_v1_order-get#shippingmethod>
 pub struct ShippingMethod {
@@ -266,14 +264,14 @@ pub struct ShippingMethod {
     pub yandex_delivery_option: Option<YandexDeliveryOption>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
-/// Возможные типы способов доставки.
-pub enum ShippingMethodType {
-    Direct,
-    Pickup,
-    Courier,
-    YandexDelivery,
+string_enum! {
+    /// Возможные типы способов доставки.
+    pub enum ShippingMethodType {
+        Direct = "DIRECT",
+        Pickup = "PICKUP",
+        Courier = "COURIER",
+        YandexDelivery = "YANDEX_DELIVERY",
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -294,16 +292,14 @@ pub struct CartItem {
 
     /// Цена за единицу товара с учётом скидок на позицию.
     /// Пример: `123.45`
-    #[serde(with = "option_string_as_float")]
-    pub discounted_unit_price: Option<f64>,
+    pub discounted_unit_price: Option<Money>,
 
     /// Промо параметры товара.
     pub features: Option<CartItemFeatures>,
 
     /// Цена за единицу товара с учётом всех скидок на позицию и на корзину.
     /// Пример: `123.45`
-    #[serde(with = "option_string_as_float")]
-    pub final_price: Option<f64>,
+    pub final_price: Option<Money>,
 
     /// Размеры и вес товара. Обязательно для товара типа PHYSICAL.
     pub measurements: Option<Measurements>,
@@ -311,16 +307,14 @@ pub struct CartItem {
     /// Количество баллов Плюса.
     /// Поле только для чтения. Переданные значения будут проигнорированы.
     /// Пример: `123.45`
-    #[serde(with = "option_string_as_float")]
-    pub points_amount: Option<f64>,
+    pub points_amount: Option<Money>,
 
     /// Данные для формирования чека.
     pub receipt: Option<ItemReceipt>,
 
     /// Суммарная цена за позицию без учета скидок.
     /// Пример: `123.45`
-    #[serde(with = "option_string_as_float")]
-    pub subtotal: Option<f64>,
+    pub subtotal: Option<Money>,
 
     /// Наименование товара.
     /// Максимальная длина: 2048
@@ -328,8 +322,7 @@ pub struct CartItem {
 
     /// Суммарная цена за позицию с учётом скидок на позицию.
     /// Пример: `123.45`
-    #[serde(with = "option_string_as_float")]
-    pub total: Option<f64>,
+    pub total: Option<Money>,
 
     /// Тип товара. Важен для интеграции с доставками.
     /// Значение по умолчанию: `UNSPECIFIED`
@@ -337,17 +330,16 @@ pub struct CartItem {
 
     /// Полная цена за единицу товара без учёта скидки.
     /// Пример: `123.45`
-    #[serde(with = "option_string_as_float")]
-    pub unit_price: Option<f64>,
+    pub unit_price: Option<Money>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
-/// Возможные типы товара.
-pub enum CartItemType {
-    Physical,
-    Digital,
-    Unspecified,
+string_enum! {
+    /// Возможные типы товара.
+    pub enum CartItemType {
+        Physical = "PHYSICAL",
+        Digital = "DIGITAL",
+        Unspecified = "UNSPECIFIED",
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Builder)]
@@ -368,13 +360,13 @@ pub struct Coupon {
     pub status: Option<CouponStatus>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
-/// Возможные статусы купона.
-pub enum CouponStatus {
-    Valid,
-    Invalid,
-    Expired,
+string_enum! {
+    /// Возможные статусы купона.
+    pub enum CouponStatus {
+        Valid = "VALID",
+        Invalid = "INVALID",
+        Expired = "EXPIRED",
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Builder)]
@@ -385,8 +377,7 @@ pub struct Discount {
     #[into]
     /// Сумма скидки.
     /// Пример: `123.45`
-    #[serde(with = "string_as_float")]
-    pub amount: f64,
+    pub amount: Money,
     #[into]
     /// Текстовое описание.
     /// Максимальная длина: 2048
@@ -423,8 +414,7 @@ pub struct Measurements {
 pub struct CourierOption {
     /// Стоимость доставки
     /// Пример: `123.45`
-    #[serde(with = "string_as_float")]
-    pub amount: f64,
+    pub amount: Money,
 
     /// Категория доставки
     pub category: CourierCategory,
@@ -446,7 +436,7 @@ pub struct CourierOption {
     pub customer_choice: Option<FlexibleCustomerChoice>,
 
     /// Ближайшая дата доставки для `type: PLAIN`. Начало интервала выбора даты доставки для `type: FLEXIBLE`
-    #[serde(with = "option_iso8601")]
+    #[serde(default, with = "option_iso8601")]
     pub from_date: Option<Time>,
 
     /// Начало интервала времени доставки. Только для `type: PLAIN`
@@ -462,7 +452,7 @@ pub struct CourierOption {
     pub time_intervals: Option<FlexibleTimeIntervals>,
 
     /// Самая поздняя дата доставки для `type: PLAIN`. Конец интервала выбора даты доставки для `type: FLEXIBLE`
-    #[serde(with = "option_iso8601")]
+    #[serde(default, with = "option_iso8601")]
     pub to_date: Option<Time>,
 
     /// Конец интервала времени доставки. Только для `type: PLAIN`
@@ -478,44 +468,45 @@ pub struct CourierOption {
     pub option_type: CourierOptionType,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
-/// Возможные категории курьерской доставки.
-pub enum CourierCategory {
-    Express,
-    Today,
-    Standard,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
-/// Возможные типы опций курьерской доставки.
-pub enum CourierOptionType {
-    #[default]
-    Plain,
-    Flexible,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
-/// Возможные типы служб доставки.
-pub enum DeliveryProvider {
-    Boxberry,
-    Cdek,
-    RussianPost,
-    Ems,
-    Courier,
-    Dhl,
-    ExpressDelivery,
-    Fivepost,
-    OzonRocket,
-    Dpd,
-    SberLogistics,
-    Pek,
-    Pickpoint,
-    Kce,
-    PonyExpress,
-    YandexDelivery,
+string_enum! {
+    /// Возможные категории курьерской доставки.
+    pub enum CourierCategory {
+        Express = "EXPRESS",
+        Today = "TODAY",
+        Standard = "STANDARD",
+    }
+}
+
+string_enum! {
+    /// Возможные типы опций курьерской доставки.
+    #[derive(Default)]
+    pub enum CourierOptionType {
+        #[default]
+        Plain = "PLAIN",
+        Flexible = "FLEXIBLE",
+    }
+}
+
+string_enum! {
+    /// Возможные типы служб доставки.
+    pub enum DeliveryProvider {
+        Boxberry = "BOXBERRY",
+        Cdek = "CDEK",
+        RussianPost = "RUSSIAN_POST",
+        Ems = "EMS",
+        Courier = "COURIER",
+        Dhl = "DHL",
+        ExpressDelivery = "EXPRESS_DELIVERY",
+        Fivepost = "FIVEPOST",
+        OzonRocket = "OZON_ROCKET",
+        Dpd = "DPD",
+        SberLogistics = "SBER_LOGISTICS",
+        Pek = "PEK",
+        Pickpoint = "PICKPOINT",
+        Kce = "KCE",
+        PonyExpress = "PONY_EXPRESS",
+        YandexDelivery = "YANDEX_DELIVERY",
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -544,13 +535,12 @@ pub struct PickupOption {
 
     /// Стоимость доставки в точку.
     /// Пример: `123.45`
-    #[serde(with = "option_string_as_float")]
-    pub amount: Option<f64>,
+    pub amount: Option<Money>,
 
     /// Дополнительное описание.
     /// Максимальная длина: 2048
     pub description: Option<String>,
-    #[serde(with = "option_iso8601")]
+    #[serde(default, with = "option_iso8601")]
     /// Ближайшая возможная дата доставки.
 
     /// Формат: `YYYY-MM-DD`
@@ -572,33 +562,33 @@ pub struct PickupOption {
     /// Срок хранения товара в точке самовывоза в днях.
     pub storage_period: Option<i32>,
 
-    #[serde(with = "option_iso8601")]
+    #[serde(default, with = "option_iso8601")]
     /// Самая поздняя дата доставки.
     /// Формат: `YYYY-MM-DD`
     pub to_date: Option<Time>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
-/// Возможные типы методов оплаты.
-pub enum AllowedPaymentMethodType {
-    Card,
-    Split,
-    CashOnDelivery,
-    CardOnDelivery,
+string_enum! {
+    /// Возможные типы методов оплаты.
+    pub enum AllowedPaymentMethodType {
+        Card = "CARD",
+        Split = "SPLIT",
+        CashOnDelivery = "CASH_ON_DELIVERY",
+        CardOnDelivery = "CARD_ON_DELIVERY",
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
-/// Возможные типы точек самовывоза.
-pub enum PickupProvider {
-    YandexMarket,
-    Boxberry,
-    Cdek,
-    InStore,
-    RussianPost,
-    Pickpoint,
-    Dpd,
+string_enum! {
+    /// Возможные типы точек самовывоза.
+    pub enum PickupProvider {
+        YandexMarket = "YANDEX_MARKET",
+        Boxberry = "BOXBERRY",
+        Cdek = "CDEK",
+        InStore = "IN_STORE",
+        RussianPost = "RUSSIAN_POST",
+        Pickpoint = "PICKPOINT",
+        Dpd = "DPD",
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -632,8 +622,7 @@ pub struct YandexDeliveryOption {
     /// Стоимость доставки.
 
     /// Пример: `123.45`
-    #[serde(with = "string_as_float")]
-    pub amount: f64,
+    pub amount: Money,
 
     /// Категория доставки.
     /// Возможные значения: `EXPRESS`, `TODAY`, `STANDARD`
@@ -655,14 +644,14 @@ pub struct YandexDeliveryOption {
     pub allowed_payment_methods: Vec<AllowedPaymentMethodType>,
 
     /// Дата и время начала доставки.
-    #[serde(with = "option_iso8601")]
+    #[serde(default, with = "option_iso8601")]
     pub from_datetime: Option<Time>,
 
     /// Чек на доставку.
     pub receipt: Option<ItemReceipt>,
 
     /// Дата и время окончания доставки.
-    #[serde(with = "option_iso8601")]
+    #[serde(default, with = "option_iso8601")]
     pub to_datetime: Option<Time>,
 }
 
@@ -728,12 +717,12 @@ pub struct FlexibleTimeIntervalsGridDescriptor {
     pub step: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
-/// Тип интервалов.
-pub enum TimeIntervalsType {
-    Grid,
-    Values,
+string_enum! {
+    /// Тип интервалов.
+    pub enum TimeIntervalsType {
+        Grid = "GRID",
+        Values = "VALUES",
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -747,3 +736,241 @@ pub struct Location {
     /// Долгота.
     pub longitude: f64,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_documented_field_names() {
+        let json = serde_json::json!({
+            "cart": {
+                "items": [{
+                    "productId": "p1",
+                    "quantity": {"count": "1"}
+                }],
+                "total": {"amount": "100.00"}
+            },
+            "currencyCode": "RUB",
+            "orderAmount": "100.00",
+            "orderId": "order",
+            "paymentMethod": {
+                "methodType": "CARD",
+                "cardLast4": "4444",
+                "cardNetwork": "VISA"
+            },
+            "shippingMethod": {
+                "methodType": "PICKUP",
+                "pickupOption": null
+            }
+        });
+        let order: BaseMerchantApiOrder = serde_json::from_value(json.clone()).unwrap();
+        let payment_method = order.payment_method.unwrap();
+        assert_eq!(payment_method.method_type, MethodType::Card);
+        assert_eq!(payment_method.card_last4.as_deref(), Some("4444"));
+        assert_eq!(payment_method.card_network, Some(CardNetwork::Visa));
+        assert_eq!(
+            order.shipping_method.unwrap().method_type,
+            ShippingMethodType::Pickup
+        );
+        assert_eq!(order.cart.cart_id, "");
+        assert_eq!(order.cart.total.amount, Money::from_rubles(100));
+
+        let cart = serde_json::to_value(&order.cart).unwrap();
+        assert!(cart.get("cartId").is_none());
+
+        let mut json = json;
+        json["cart"].as_object_mut().unwrap().remove("total");
+        assert!(serde_json::from_value::<BaseMerchantApiOrder>(json).is_err());
+    }
+
+    #[test]
+    fn test_unknown_enum_values() {
+        let option = |id: &str| {
+            serde_json::json!({
+                "amount": "100.00",
+                "category": "HYPERSONIC",
+                "title": "Доставка",
+                "allowedPaymentMethods": ["CRYPTO"],
+                "provider": "TELEPORT",
+                "courierOptionId": id,
+                "yandexDeliveryOptionId": id,
+                "optionType": "SCHEDULED",
+                "timeIntervals": {"type": "RANDOM"}
+            })
+        };
+        let json = serde_json::json!({
+            "delivery": {"price": "100.00", "status": "LOST_IN_SPACE"},
+            "operations": [{
+                "amount": "100.00",
+                "operationId": "op",
+                "operationType": "CHARGEBACK",
+                "orderId": "order",
+                "status": "DISPUTED"
+            }],
+            "order": {
+                "cart": {
+                    "items": [{
+                        "productId": "p1",
+                        "quantity": {"count": "1"},
+                        "itemType": "VIRTUAL",
+                        "receipt": {
+                            "tax": 42,
+                            "measure": 99,
+                            "paymentMethodType": 8,
+                            "paymentSubjectType": 27,
+                            "agent": {"agentType": 8, "phones": []}
+                        }
+                    }],
+                    "coupons": [{"value": "SALE", "status": "USED"}],
+                    "total": {"amount": "100.00"}
+                },
+                "currencyCode": "USD",
+                "orderAmount": "100.00",
+                "orderId": "order",
+                "paymentMethod": {"methodType": "BNPL", "cardNetwork": "NEWCARD"},
+                "paymentStatus": "ON_HOLD",
+                "shippingMethod": {
+                    "methodType": "DRONE",
+                    "courierOption": option("courier"),
+                    "yandexDeliveryOption": option("yandex"),
+                    "pickupOption": {
+                        "address": "Москва",
+                        "location": {"latitude": 55.75, "longitude": 37.61},
+                        "pickupPointId": "point",
+                        "title": "Постамат",
+                        "provider": "LOCKER_CO"
+                    }
+                }
+            }
+        });
+        let data: OrderResponseData = serde_json::from_value(json.clone()).unwrap();
+
+        let unknown = |value: &str| value.to_string();
+        assert_eq!(
+            data.delivery.as_ref().unwrap().status,
+            DeliveryStatus::Unknown(unknown("LOST_IN_SPACE"))
+        );
+        let operation = &data.operations[0];
+        assert_eq!(
+            operation.operation_type,
+            OperationType::Unknown(unknown("CHARGEBACK"))
+        );
+        assert_eq!(
+            operation.status,
+            OperationStatus::Unknown(unknown("DISPUTED"))
+        );
+        let order = data.order.as_ref().unwrap();
+        assert_eq!(
+            order.payment_status,
+            Some(PaymentStatus::Unknown(unknown("ON_HOLD")))
+        );
+        assert_eq!(order.currency_code, CurrencyCode::Unknown(unknown("USD")));
+        assert_eq!(
+            order.cart.items[0].item_type,
+            Some(CartItemType::Unknown(unknown("VIRTUAL")))
+        );
+        assert_eq!(
+            order.cart.coupons[0].status,
+            Some(CouponStatus::Unknown(unknown("USED")))
+        );
+        let receipt = order.cart.items[0].receipt.as_ref().unwrap();
+        assert_eq!(receipt.tax, Tax::Unknown(42));
+        assert_eq!(receipt.measure, Some(Measure::Unknown(99)));
+        assert_eq!(
+            receipt.payment_method_type,
+            Some(PaymentMethodType::Unknown(8))
+        );
+        assert_eq!(
+            receipt.payment_subject_type,
+            Some(PaymentSubjectType::Unknown(27))
+        );
+        assert_eq!(
+            receipt.agent.as_ref().unwrap().agent_type,
+            AgentType::Unknown(8)
+        );
+        let payment_method = order.payment_method.as_ref().unwrap();
+        assert_eq!(
+            payment_method.method_type,
+            MethodType::Unknown(unknown("BNPL"))
+        );
+        assert_eq!(
+            payment_method.card_network,
+            Some(CardNetwork::Unknown(unknown("NEWCARD")))
+        );
+        let shipping = order.shipping_method.as_ref().unwrap();
+        assert_eq!(
+            shipping.method_type,
+            ShippingMethodType::Unknown(unknown("DRONE"))
+        );
+        let courier = shipping.courier_option.as_ref().unwrap();
+        assert_eq!(
+            courier.category,
+            CourierCategory::Unknown(unknown("HYPERSONIC"))
+        );
+        assert_eq!(
+            courier.provider,
+            Some(DeliveryProvider::Unknown(unknown("TELEPORT")))
+        );
+        assert_eq!(
+            courier.option_type,
+            CourierOptionType::Unknown(unknown("SCHEDULED"))
+        );
+        assert_eq!(
+            courier.allowed_payment_methods,
+            vec![AllowedPaymentMethodType::Unknown(unknown("CRYPTO"))]
+        );
+        assert_eq!(
+            courier.time_intervals.as_ref().unwrap().time_intervals_type,
+            TimeIntervalsType::Unknown(unknown("RANDOM"))
+        );
+        assert_eq!(
+            shipping.pickup_option.as_ref().unwrap().provider,
+            Some(PickupProvider::Unknown(unknown("LOCKER_CO")))
+        );
+
+        let serialized = serde_json::to_value(&data).unwrap();
+        assert_eq!(serialized["order"]["paymentStatus"], "ON_HOLD");
+        assert_eq!(
+            serialized["order"]["shippingMethod"]["courierOption"]["provider"],
+            "TELEPORT"
+        );
+        assert_eq!(serialized["operations"][0]["operationType"], "CHARGEBACK");
+        assert_eq!(
+            serialized["order"]["cart"]["items"][0]["receipt"]["tax"],
+            42
+        );
+
+        let operation: OperationResponseData = serde_json::from_value(serde_json::json!({
+            "operation": {
+                "amount": "100.00",
+                "operationId": "op",
+                "operationType": "CHARGEBACK",
+                "orderId": "order",
+                "status": "DISPUTED"
+            }
+        }))
+        .unwrap();
+        assert_eq!(
+            operation.operation.operation_type,
+            OperationType::Unknown(unknown("CHARGEBACK"))
+        );
+        assert_eq!(
+            operation.operation.status,
+            OperationStatus::Unknown(unknown("DISPUTED"))
+        );
+    }
+
+    #[test]
+    fn test_known_enum_values() {
+        let status: PaymentStatus = serde_json::from_str(r#""PARTIALLY_REFUNDED""#).unwrap();
+        assert_eq!(status, PaymentStatus::PartiallyRefunded);
+        assert_eq!(
+            serde_json::to_string(&status).unwrap(),
+            r#""PARTIALLY_REFUNDED""#
+        );
+        let network: CardNetwork = serde_json::from_str(r#""UNKNOWN""#).unwrap();
+        assert_eq!(network, CardNetwork::Unknown("UNKNOWN".into()));
+        assert_eq!(DeliveryProvider::RussianPost.to_string(), "RUSSIAN_POST");
+    }
+}
@@ -1,3 +1,4 @@
+use crate::Money;
 use crate::serde_help::*;
 use builder_pattern::Builder;
 use serde::{Deserialize, Serialize};
@@ -7,10 +8,9 @@ use serde::{Deserialize, Serialize};
 /// Тело запроса на возврат
 /// <https://pay.yandex.ru/docs/ru/custom/backend/yandex-pay-api/order/merchant_v2_refund-post#body>
 pub struct RefundRequest {
-    #[serde(with = "string_as_float")]
     /// Сумма к возврату
     /// Example: 123.45
-    pub refund_amount: f64,
+    pub refund_amount: Money,
     #[default(None)]
     #[serde(skip_serializing_if = "Option::is_none")]
     /// Идентификатор точки продаж
@@ -68,13 +68,12 @@ pub struct TargetCartItem {
     pub product_id: String,
     #[default(None)]
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[serde(with = "option_string_as_float")]
     /// Цена одной единицы товара/услуги после выполнения операции.
     /// Необходимо указать, если цена одной единицы уменьшается в результате операции.
     /// Это может быть полезным, если необходимо вернуть часть денег за товар или подтверждении заказа.
     /// Если не указывать это поле в запросе, то считается, что цена осталась прежней.
     /// Example: 123.45
-    pub price: Option<f64>,
+    pub price: Option<Money>,
     #[default(None)]
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(with = "option_string_as_float")]
@@ -89,8 +88,7 @@ pub struct TargetCartItem {
 /// Описывает итоговое состояние доставки
 /// <https://pay.yandex.ru/docs/ru/custom/backend/yandex-pay-api/order/merchant_v2_refund-post#targetshipping>
 pub struct TargetShipping {
-    #[serde(with = "string_as_float")]
     /// Стоимость доставки после выполнения операции
     /// Example: 123.45
-    pub amount: f64,
+    pub amount: Money,
 }
@@ -1,4 +1,4 @@
-use crate::{orders_capture::CartWithRequiredTotalWithoutFinalPrice, serde_help::*};
+use crate::{Money, orders_capture::CartWithRequiredTotalWithoutFinalPrice};
 use builder_pattern::Builder;
 use serde::{Deserialize, Serialize};
 #[derive(Debug, Clone, Serialize, Deserialize, Builder)]
@@ -15,9 +15,8 @@ pub struct SubmitRequest {
     pub cart: Option<CartWithRequiredTotalWithoutFinalPrice>,
     #[default(None)]
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[serde(with = "option_string_as_float")]
     /// Итоговая сумма заказа. Равна cart.total.amount.
     /// Является обязательным полем, если передается cart.
     /// Example: 123.45
-    pub order_amount: Option<f64>,
+    pub order_amount: Option<Money>,
 }
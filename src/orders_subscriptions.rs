@@ -1,8 +1,8 @@
 use builder_pattern::Builder;
 use serde::{Deserialize, Serialize};
 
+use crate::Money;
 use crate::orders::*;
-use crate::serde_help::*;
 
 #[derive(Debug, Clone, Serialize, Deserialize, Builder)]
 #[serde(rename_all = "camelCase")]
@@ -20,9 +20,8 @@ pub struct CreateSubscriptionRequest {
     pub cart: Option<RenderedCart>,
     #[default(None)]
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[serde(with = "option_string_as_float")]
     /// Сумма, которую будет списана в будущем
-    pub future_write_off_amount: Option<f64>,
+    pub future_write_off_amount: Option<Money>,
     #[default(None)]
     #[serde(skip_serializing_if = "Option::is_none")]
     /// Количество периодов подписки
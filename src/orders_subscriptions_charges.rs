@@ -0,0 +1,48 @@
+use builder_pattern::Builder;
+use serde::{Deserialize, Serialize};
+
+use crate::serde_help::*;
+use crate::*;
+
+/// Параметры списка рекуррентных списаний подписки. Передаются в строке запроса.
+#[derive(Debug, Clone, Serialize, Deserialize, Builder)]
+#[serde(rename_all = "camelCase")]
+pub struct ListSubscriptionChargesRequest {
+    #[default(None)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Максимальное количество списаний в ответе
+    pub limit: Option<u32>,
+    #[default(None)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Количество пропускаемых списаний, от самого раннего
+    pub offset: Option<u32>,
+}
+
+/// Подписка и её рекуррентные списания по стартовому заказу
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubscriptionChargesResponseData {
+    pub customer_subscription_id: String,
+    /// Идентификатор стартового заказа
+    pub parent_order_id: String,
+    pub status: SubscriptionStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(with = "option_iso8601")]
+    pub cancelled_at: Option<Time>,
+    #[serde(default)]
+    pub charges: Vec<SubscriptionCharge>,
+}
+
+/// Рекуррентное списание по подписке
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubscriptionCharge {
+    /// Идентификатор заказа списания на стороне продавца
+    pub order_id: String,
+    pub amount: Money,
+    pub payment_status: Option<PaymentStatus>,
+    /// Идентификатор операции `RECURRING`
+    pub operation_id: Option<String>,
+    #[serde(default, with = "option_iso8601")]
+    pub created: Option<Time>,
+}
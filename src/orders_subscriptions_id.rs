@@ -3,8 +3,8 @@ use crate::serde_help::*;
 use builder_pattern::Builder;
 use serde::{Deserialize, Serialize};
 
-/// Запрос на рекуррентное списание по подписке
-/// <https://pay.yandex.ru/docs/ru/custom/backend/yandex-pay-api/subscriptions/merchant_v1_customer_subscription-get#body>
+/// Параметры запроса подписки. Передаются в строке запроса, а не в теле GET-запроса.
+/// <https://pay.yandex.ru/docs/ru/custom/backend/yandex-pay-api/subscriptions/merchant_v1_customer_subscription-get#query>
 #[derive(Debug, Clone, Serialize, Deserialize, Builder)]
 #[serde(rename_all = "camelCase")]
 pub struct GetSubscriptionRequest {
@@ -19,21 +19,39 @@ pub struct GetSubscriptionRequest {
 pub struct CustomerSubscriptionResponseData {
     pub status: SubscriptionStatus,
     pub subscription_plan_id: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     #[serde(with = "option_iso8601")]
     pub cancelled_at: Option<Time>,
     pub customer_subscription_id: String,
     pub is_card_active: bool,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     #[serde(with = "option_iso8601")]
     pub next_write_off: Option<Time>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
-pub enum SubscriptionStatus {
-    New,
-    Active,
-    Cancelled,
-    Expired,
+string_enum! {
+    pub enum SubscriptionStatus {
+        New = "NEW",
+        Active = "ACTIVE",
+        Cancelled = "CANCELLED",
+        Expired = "EXPIRED",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unknown_subscription_status() {
+        let json = serde_json::json!({
+            "status": "PAUSED",
+            "subscriptionPlanId": "plan",
+            "customerSubscriptionId": "sub",
+            "isCardActive": true
+        });
+        let data: CustomerSubscriptionResponseData = serde_json::from_value(json).unwrap();
+        assert_eq!(data.status, SubscriptionStatus::Unknown("PAUSED".into()));
+        assert_eq!(serde_json::to_value(&data).unwrap()["status"], "PAUSED");
+    }
 }
@@ -1,8 +1,8 @@
 use builder_pattern::Builder;
 use serde::{Deserialize, Serialize};
 
+use crate::Money;
 use crate::orders::*;
-use crate::serde_help::*;
 
 /// Запрос на рекуррентное списание по подписке
 /// <https://pay.yandex.ru/docs/ru/custom/backend/yandex-pay-api/subscriptions/merchant_v1_subscriptions_recur-post#body>
@@ -11,8 +11,7 @@ use crate::serde_help::*;
 pub struct CreateRecurrentChargeRequest {
     #[into]
     /// Сумма списания
-    #[serde(with = "string_as_float")]
-    pub amount: f64,
+    pub amount: Money,
 
     /// Корзина
     pub cart: RenderedCart,
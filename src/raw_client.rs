@@ -0,0 +1,127 @@
+//! Объектно-безопасный транспорт для `dyn`-клиентов.
+//!
+//! [`RawHttpClient`] возвращает статус и тело ответа как есть, а JSON разбирается уже
+//! в [`YandexPayApi`]. Это позволяет выбирать транспорт во время выполнения через
+//! [`DynYandexPayApi`].
+
+use std::pin::Pin;
+
+use bytes::Bytes;
+
+use crate::*;
+
+/// Сырой HTTP-ответ API
+#[derive(Debug, Clone)]
+pub struct RawResponse {
+    pub status: u16,
+    pub body: Bytes,
+}
+
+impl RawResponse {
+    /// Разбирает ответ через [`decode_response`]
+    pub fn decode<T: serde::de::DeserializeOwned>(&self) -> R<T> {
+        decode_response(self.status, &self.body)
+    }
+}
+
+pub type RawFuture<'a> = Pin<Box<dyn Future<Output = R<RawResponse>> + Send + 'a>>;
+
+/// Транспорт без обобщённых методов, пригодный для `dyn RawHttpClient`.
+///
+/// Ошибкой считаются только сбои транспорта: ответы с любым HTTP-статусом возвращаются
+/// как [`RawResponse`].
+pub trait RawHttpClient: Send + Sync {
+    fn send_raw(&self, request: YandexPayApiRequest) -> RawFuture<'_>;
+}
+
+impl HttpClient for Arc<dyn RawHttpClient> {
+    async fn send<T: serde::de::DeserializeOwned>(&self, request: YandexPayApiRequest) -> R<T> {
+        self.send_raw(request).await?.decode()
+    }
+}
+
+/// Клиент с транспортом, выбираемым во время выполнения.
+///
+/// ```no_run
+/// # use std::sync::Arc;
+/// # use yandex_pay_api::*;
+/// # fn client(
+/// #     real: impl RawHttpClient + 'static,
+/// #     fake: impl RawHttpClient + 'static,
+/// #     offline: bool,
+/// # ) -> DynYandexPayApi {
+/// let transport: Arc<dyn RawHttpClient> = if offline {
+///     Arc::new(fake)
+/// } else {
+///     Arc::new(real)
+/// };
+/// YandexPayApi::new("https://sandbox.pay.yandex.ru".into(), "merchant-id".into(), transport)
+/// # }
+/// ```
+pub type DynYandexPayApi = YandexPayApi<Arc<dyn RawHttpClient>>;
+
+impl<C: HttpClient + RawHttpClient + 'static> YandexPayApi<C> {
+    /// Стирает тип транспорта, сохраняя остальные настройки клиента
+    pub fn into_dyn(self) -> DynYandexPayApi {
+        YandexPayApi {
+            client: Arc::new(self.client),
+            base_url: self.base_url,
+            api_key: self.api_key,
+            retry_policy: self.retry_policy,
+            request_timeout: self.request_timeout,
+            validate_requests: self.validate_requests,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use super::*;
+
+    /// Транспорт, всегда отвечающий одним и тем же статусом и телом
+    struct StaticClient(u16, &'static str);
+
+    impl RawHttpClient for StaticClient {
+        fn send_raw(&self, _: YandexPayApiRequest) -> RawFuture<'_> {
+            let response = RawResponse {
+                status: self.0,
+                body: Bytes::from_static(self.1.as_bytes()),
+            };
+            Box::pin(async move { Ok(response) })
+        }
+    }
+
+    fn api(transport: Arc<dyn RawHttpClient>) -> DynYandexPayApi {
+        YandexPayApi::new("http://localhost".into(), "key".into(), transport)
+    }
+
+    #[tokio::test]
+    async fn test_switch_transports_at_runtime() {
+        let fake = FakeYandexPay::new();
+        let apis = [
+            api(Arc::new(fake.clone())),
+            api(Arc::new(StaticClient(
+                404,
+                r#"{"status": "fail", "code": 404, "reasonCode": "ORDER_NOT_FOUND"}"#,
+            ))),
+        ];
+        for api in apis {
+            let err = api.get_order("missing").await.unwrap_err();
+            assert_eq!(err.reason_code(), Some(&ReasonCode::OrderNotFound));
+            assert_eq!(err.http_status(), Some(404));
+        }
+        assert_eq!(fake.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_into_dyn_keeps_settings() {
+        let fake = FakeYandexPay::new();
+        let api = YandexPayApi::new("http://localhost".into(), "key".into(), fake.clone())
+            .with_validation(true)
+            .with_request_timeout(std::time::Duration::from_secs(1))
+            .into_dyn();
+        assert!(api.validate_requests);
+        assert!(api.get_operation("missing").await.is_err());
+        assert_eq!(fake.requests()[0].request_timeout, 1000);
+    }
+}
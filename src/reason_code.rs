@@ -0,0 +1,138 @@
+use crate::serde_help::string_enum;
+use crate::*;
+
+string_enum! {
+    /// Код причины ошибки (`reasonCode`) из ответа Yandex Pay API.
+    ///
+    /// Неизвестные коды сохраняются в [`ReasonCode::Unknown`].
+    pub enum ReasonCode {
+        /// Заказ не найден
+        OrderNotFound = "ORDER_NOT_FOUND",
+        /// Заказ с таким идентификатором уже существует
+        OrderAlreadyExists = "ORDER_ALREADY_EXISTS",
+        /// Операция недоступна в текущем статусе заказа
+        OrderHasInvalidStatus = "ORDER_HAS_INVALID_STATUS",
+        /// Сумма не совпадает с суммой заказа или корзины
+        AmountMismatch = "AMOUNT_MISMATCH",
+        /// Операция с таким `externalOperationId` уже существует, см. [`RefundRequest::external_operation_id`]
+        DuplicateExternalOperationId = "DUPLICATE_EXTERNAL_OPERATION_ID",
+        /// Операция не найдена
+        OperationNotFound = "OPERATION_NOT_FOUND",
+        /// Подписка не найдена
+        SubscriptionNotFound = "SUBSCRIPTION_NOT_FOUND",
+        /// Некорректный запрос
+        BadRequest = "BAD_REQUEST",
+        /// Ключ API не прошёл проверку
+        Unauthorized = "UNAUTHORIZED",
+        /// Доступ запрещён
+        Forbidden = "FORBIDDEN",
+        /// Ресурс не найден
+        NotFound = "NOT_FOUND",
+        /// Слишком много запросов
+        TooManyRequests = "TOO_MANY_REQUESTS",
+        /// Внутренняя ошибка Yandex Pay
+        InternalServerError = "INTERNAL_SERVER_ERROR",
+    }
+}
+
+impl YandexPayApiError {
+    /// Ответ API с описанием ошибки, если ошибка пришла от Yandex Pay
+    pub fn api_error(&self) -> Option<&YandexPayApiResponseError> {
+        match self {
+            YandexPayApiError::Api(error) => Some(error),
+            _ => None,
+        }
+    }
+
+    /// HTTP-статус ответа, если он был получен
+    pub fn http_status(&self) -> Option<u16> {
+        let error = self.api_error()?;
+        error
+            .http_status
+            .or_else(|| error.code.and_then(|code| u16::try_from(code).ok()))
+    }
+
+    pub fn reason_code(&self) -> Option<&ReasonCode> {
+        self.api_error()?.reason_code.as_ref()
+    }
+
+    /// Ошибка временная, и запрос имеет смысл повторить: ошибки транспорта, таймауты, 429 и 5xx.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            YandexPayApiError::Transport(_) | YandexPayApiError::Timeout => true,
+            YandexPayApiError::Api(error) => {
+                matches!(
+                    error.reason_code,
+                    Some(ReasonCode::TooManyRequests | ReasonCode::InternalServerError)
+                ) || matches!(self.http_status(), Some(429 | 500..=599))
+            }
+            _ => false,
+        }
+    }
+
+    /// Запрос конфликтует с текущим состоянием: заказ или операция уже существуют,
+    /// либо операция недоступна в текущем статусе заказа.
+    pub fn is_conflict(&self) -> bool {
+        matches!(
+            self.reason_code(),
+            Some(
+                ReasonCode::OrderAlreadyExists
+                    | ReasonCode::DuplicateExternalOperationId
+                    | ReasonCode::OrderHasInvalidStatus
+            )
+        ) || self.http_status() == Some(409)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(json: serde_json::Value) -> YandexPayApiError {
+        YandexPayApiError::Api(serde_json::from_value(json).unwrap())
+    }
+
+    #[test]
+    fn test_parse_reason_code() {
+        let err = error(serde_json::json!({
+            "status": "fail",
+            "code": 404,
+            "reasonCode": "ORDER_NOT_FOUND",
+            "reason": "Order not found",
+            "details": {"orderId": "o1"}
+        }));
+        assert_eq!(err.reason_code(), Some(&ReasonCode::OrderNotFound));
+        let api_error = err.api_error().unwrap();
+        assert_eq!(api_error.reason.as_deref(), Some("Order not found"));
+        assert_eq!(api_error.details.as_ref().unwrap()["orderId"], "o1");
+        assert_eq!(err.http_status(), Some(404));
+        assert!(!err.is_retryable());
+        assert!(!err.is_conflict());
+
+        let err = error(serde_json::json!({"status": "fail", "reasonCode": "SOMETHING_NEW"}));
+        assert_eq!(
+            err.reason_code(),
+            Some(&ReasonCode::Unknown("SOMETHING_NEW".into()))
+        );
+        assert_eq!(
+            serde_json::to_value(err.reason_code()).unwrap(),
+            "SOMETHING_NEW"
+        );
+    }
+
+    #[test]
+    fn test_classifiers() {
+        let err = error(serde_json::json!({
+            "status": "fail",
+            "code": 409,
+            "reasonCode": "DUPLICATE_EXTERNAL_OPERATION_ID"
+        }));
+        assert!(err.is_conflict());
+        assert!(!err.is_retryable());
+
+        let err = error(serde_json::json!({"status": "fail", "code": 503}));
+        assert!(err.is_retryable());
+        assert!(YandexPayApiError::Timeout.is_retryable());
+        assert!(!YandexPayApiError::Timeout.is_conflict());
+    }
+}
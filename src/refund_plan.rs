@@ -0,0 +1,445 @@
+use crate::*;
+
+/// Позиция возврата
+#[derive(Debug, Clone, PartialEq)]
+pub enum RefundLine {
+    /// Вернуть `count` единиц товара
+    Units { product_id: String, count: f64 },
+    /// Уменьшить стоимость позиции на `amount`
+    Amount { product_id: String, amount: Money },
+}
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum RefundPlanError {
+    #[error("Nothing to refund")]
+    Empty,
+    #[error("Product {0:?} is not in the order cart")]
+    UnknownProduct(String),
+    #[error("Product {0:?} has no price in the order cart")]
+    MissingPrice(String),
+    #[error("Invalid refund for product {0:?}")]
+    Invalid(String),
+    #[error("Cannot refund {requested} units of {product_id:?}, only {available} left")]
+    QuantityExceeded {
+        product_id: String,
+        requested: f64,
+        available: f64,
+    },
+    #[error("Cannot reduce {product_id:?} by {requested}, only {available} left")]
+    AmountExceeded {
+        product_id: String,
+        requested: Money,
+        available: Money,
+    },
+    #[error("{total} cannot be split evenly between {count} units of {product_id:?}")]
+    Indivisible {
+        product_id: String,
+        total: Money,
+        count: f64,
+    },
+    #[error("Refund amount {requested} exceeds remaining {remaining}")]
+    ExceedsRemaining { requested: Money, remaining: Money },
+}
+
+/// Состояние позиции корзины во время планирования
+#[derive(Debug, Clone)]
+struct PlannedItem {
+    product_id: String,
+    count: f64,
+    /// Цена единицы, `None`, если сумму позиции нельзя точно разделить на количество
+    price: Option<Money>,
+    total: Money,
+    changed_price: bool,
+}
+
+impl PlannedItem {
+    fn indivisible(&self, total: Money) -> RefundPlanError {
+        RefundPlanError::Indivisible {
+            product_id: self.product_id.clone(),
+            total,
+            count: self.count,
+        }
+    }
+}
+
+/// Цена единицы, при которой `count` единиц стоят ровно `total`
+fn exact_unit_price(total: Money, count: f64) -> Option<Money> {
+    if count <= 0.0 {
+        return None;
+    }
+    let price = Money::from_kopecks((total.kopecks() as f64 / count).round() as i64);
+    (price.mul_quantity(count) == total).then_some(price)
+}
+
+/// Планировщик частичного возврата.
+///
+/// Строит [`RefundRequest`] с `target_cart` — итоговым состоянием корзины заказа после возврата —
+/// и `refund_amount`, равным разнице стоимости корзины до и после возврата.
+/// Возвраты сверх `order_amount`, который уже учитывает успешные возвраты, за вычетом
+/// незавершённых операций `REFUND` отклоняются.
+/// Если уменьшенную стоимость позиции нельзя точно разделить на количество единиц,
+/// возвращается [`RefundPlanError::Indivisible`], а не округлённая цена.
+///
+/// ```no_run
+/// # use yandex_pay_api::*;
+/// # async fn refund<C: HttpClient>(api: YandexPayApi<C>) -> Result<(), Box<dyn std::error::Error>> {
+/// let data = api.get_order("order_123").await?;
+/// let request = RefundPlanner::from_order_response(&data)
+///     .ok_or("order is missing")?
+///     .refund_units("prod_1", 1.0)
+///     .refund_amount("prod_2", Money::from_rubles(100))
+///     .build()?;
+/// api.refund_order("order_123", request).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct RefundPlanner<'a> {
+    order: &'a BaseMerchantApiOrder,
+    operations: &'a [OrderResponseOperation],
+    lines: Vec<RefundLine>,
+}
+
+impl<'a> RefundPlanner<'a> {
+    pub fn new(order: &'a BaseMerchantApiOrder, operations: &'a [OrderResponseOperation]) -> Self {
+        RefundPlanner {
+            order,
+            operations,
+            lines: vec![],
+        }
+    }
+
+    /// Планировщик для ответа `get_order`, `None`, если в ответе нет заказа
+    pub fn from_order_response(data: &'a OrderResponseData) -> Option<Self> {
+        data.order
+            .as_ref()
+            .map(|order| RefundPlanner::new(order, &data.operations))
+    }
+
+    pub fn line(mut self, line: RefundLine) -> Self {
+        self.lines.push(line);
+        self
+    }
+
+    /// Вернуть `count` единиц товара `product_id`
+    pub fn refund_units(self, product_id: impl Into<String>, count: f64) -> Self {
+        self.line(RefundLine::Units {
+            product_id: product_id.into(),
+            count,
+        })
+    }
+
+    /// Уменьшить стоимость позиции `product_id` на `amount`
+    pub fn refund_amount(self, product_id: impl Into<String>, amount: Money) -> Self {
+        self.line(RefundLine::Amount {
+            product_id: product_id.into(),
+            amount,
+        })
+    }
+
+    /// Сумма незавершённых операций `REFUND`. Она ещё не вычтена из `order_amount`.
+    pub fn pending_refund_amount(&self) -> Money {
+        self.operations
+            .iter()
+            .filter(|op| op.operation_type == OperationType::Refund)
+            .filter(|op| !op.status.is_terminal())
+            .map(|op| op.amount)
+            .sum()
+    }
+
+    /// Сумма, доступная для возврата: `order_amount` уже учитывает успешные возвраты,
+    /// незавершённые вычитаются отдельно
+    pub fn remaining_amount(&self) -> Money {
+        self.order.order_amount - self.pending_refund_amount()
+    }
+
+    pub fn build(&self) -> Result<RefundRequest, RefundPlanError> {
+        if self.lines.is_empty() {
+            return Err(RefundPlanError::Empty);
+        }
+        let mut items = self
+            .order
+            .cart
+            .items
+            .iter()
+            .map(|item| {
+                let count = item.quantity.count;
+                let price = item
+                    .final_price
+                    .or(item.discounted_unit_price)
+                    .or(item.unit_price)
+                    .or_else(|| exact_unit_price(item.total?, count));
+                let total = price
+                    .map(|price| price.mul_quantity(count))
+                    .or(item.total)
+                    .ok_or_else(|| RefundPlanError::MissingPrice(item.product_id.clone()))?;
+                Ok(PlannedItem {
+                    product_id: item.product_id.clone(),
+                    count,
+                    price,
+                    total,
+                    changed_price: false,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let before: Money = items.iter().map(|item| item.total).sum();
+        for line in &self.lines {
+            apply(&mut items, line)?;
+        }
+        let after: Money = items.iter().map(|item| item.total).sum();
+        let refund_amount = before - after;
+        let remaining = self.remaining_amount();
+        if refund_amount > remaining {
+            return Err(RefundPlanError::ExceedsRemaining {
+                requested: refund_amount,
+                remaining,
+            });
+        }
+        let target_cart = TargetCart::new()
+            .items(
+                items
+                    .into_iter()
+                    .map(|item| {
+                        TargetCartItem::new()
+                            .product_id(item.product_id)
+                            .price(item.price.filter(|_| item.changed_price))
+                            .quantity_count(Some(item.count))
+                            .build()
+                    })
+                    .collect(),
+            )
+            .build();
+        Ok(RefundRequest::new()
+            .refund_amount(refund_amount)
+            .target_cart(Some(target_cart))
+            .build())
+    }
+}
+
+fn apply(items: &mut [PlannedItem], line: &RefundLine) -> Result<(), RefundPlanError> {
+    let product_id = match line {
+        RefundLine::Units { product_id, .. } | RefundLine::Amount { product_id, .. } => product_id,
+    };
+    let item = items
+        .iter_mut()
+        .find(|item| &item.product_id == product_id)
+        .ok_or_else(|| RefundPlanError::UnknownProduct(product_id.clone()))?;
+    match *line {
+        RefundLine::Units { count, .. } => {
+            if count <= 0.0 || count.is_nan() {
+                return Err(RefundPlanError::Invalid(product_id.clone()));
+            }
+            if count > item.count {
+                return Err(RefundPlanError::QuantityExceeded {
+                    product_id: product_id.clone(),
+                    requested: count,
+                    available: item.count,
+                });
+            }
+            let price = item.price.ok_or_else(|| item.indivisible(item.total))?;
+            item.count -= count;
+            item.total = price.mul_quantity(item.count);
+        }
+        RefundLine::Amount { amount, .. } => {
+            let total = item.total;
+            if amount <= Money::ZERO || item.count <= 0.0 {
+                return Err(RefundPlanError::Invalid(product_id.clone()));
+            }
+            if amount > total {
+                return Err(RefundPlanError::AmountExceeded {
+                    product_id: product_id.clone(),
+                    requested: amount,
+                    available: total,
+                });
+            }
+            let total = total - amount;
+            let price =
+                exact_unit_price(total, item.count).ok_or_else(|| item.indivisible(total))?;
+            item.price = Some(price);
+            item.total = total;
+            item.changed_price = true;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn order() -> BaseMerchantApiOrder {
+        serde_json::from_value(serde_json::json!({
+            "cart": {
+                "cartId": "cart",
+                "items": [
+                    {
+                        "productId": "a",
+                        "quantity": {"count": "3", "available": "3"},
+                        "unitPrice": "100.00",
+                        "discountedUnitPrice": "90.00",
+                        "total": "270.00"
+                    },
+                    {
+                        "productId": "b",
+                        "quantity": {"count": "1", "available": "1"},
+                        "total": "500.00"
+                    }
+                ],
+                "total": {"amount": "770.00"}
+            },
+            "currencyCode": "RUB",
+            "orderAmount": "770.00",
+            "orderId": "order",
+            "created": null,
+            "updated": null
+        }))
+        .unwrap()
+    }
+
+    fn refund(amount: i64, status: OperationStatus) -> OrderResponseOperation {
+        serde_json::from_value(serde_json::json!({
+            "amount": Money::from_rubles(amount),
+            "operationId": "op",
+            "operationType": "REFUND",
+            "orderId": "order",
+            "status": status,
+            "created": null,
+            "updated": null
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_plan_units_and_amount() {
+        let order = order();
+        let request = RefundPlanner::new(&order, &[])
+            .refund_units("a", 2.0)
+            .refund_amount("b", Money::from_rubles(150))
+            .build()
+            .unwrap();
+        assert_eq!(request.refund_amount, Money::from_rubles(330));
+        let items = request.target_cart.unwrap().items;
+        assert_eq!(items[0].product_id, "a");
+        assert_eq!(items[0].quantity_count, Some(1.0));
+        assert_eq!(items[0].price, None);
+        assert_eq!(items[1].quantity_count, Some(1.0));
+        assert_eq!(items[1].price, Some(Money::from_rubles(350)));
+    }
+
+    #[test]
+    fn test_refuse_exceeding_refunds() {
+        let order = order();
+        let planner = RefundPlanner::new(&order, &[]);
+        assert!(matches!(
+            planner.clone().refund_units("a", 4.0).build(),
+            Err(RefundPlanError::QuantityExceeded { .. })
+        ));
+        assert!(matches!(
+            planner.clone().refund_units("c", 1.0).build(),
+            Err(RefundPlanError::UnknownProduct(_))
+        ));
+        assert_eq!(planner.build().unwrap_err(), RefundPlanError::Empty);
+
+        let mut order = order;
+        order.order_amount = Money::from_rubles(170);
+        let operations = [
+            refund(600, OperationStatus::Success),
+            refund(700, OperationStatus::Fail),
+        ];
+        let planner = RefundPlanner::new(&order, &operations);
+        assert_eq!(planner.pending_refund_amount(), Money::ZERO);
+        assert_eq!(planner.remaining_amount(), Money::from_rubles(170));
+        let request = planner.clone().refund_units("a", 1.0).build().unwrap();
+        assert_eq!(request.refund_amount, Money::from_rubles(90));
+        assert_eq!(
+            planner.refund_units("b", 1.0).build().unwrap_err(),
+            RefundPlanError::ExceedsRemaining {
+                requested: Money::from_rubles(500),
+                remaining: Money::from_rubles(170),
+            }
+        );
+    }
+
+    #[test]
+    fn test_pending_refund_reduces_remaining() {
+        let mut order = order();
+        order.order_amount = Money::from_rubles(170);
+        let operations = [
+            refund(600, OperationStatus::Success),
+            refund(100, OperationStatus::Pending),
+        ];
+        let planner = RefundPlanner::new(&order, &operations);
+        assert_eq!(planner.pending_refund_amount(), Money::from_rubles(100));
+        assert_eq!(planner.remaining_amount(), Money::from_rubles(70));
+        assert_eq!(
+            planner.clone().refund_units("a", 1.0).build().unwrap_err(),
+            RefundPlanError::ExceedsRemaining {
+                requested: Money::from_rubles(90),
+                remaining: Money::from_rubles(70),
+            }
+        );
+        let request = planner
+            .refund_amount("b", Money::from_rubles(70))
+            .build()
+            .unwrap();
+        assert_eq!(request.refund_amount, Money::from_rubles(70));
+    }
+
+    #[test]
+    fn test_refuse_indivisible_amount() {
+        let order: BaseMerchantApiOrder = serde_json::from_value(serde_json::json!({
+            "cart": {
+                "items": [
+                    {
+                        "productId": "a",
+                        "quantity": {"count": "3", "available": "3"},
+                        "unitPrice": "100.00",
+                        "total": "300.00"
+                    },
+                    {
+                        "productId": "b",
+                        "quantity": {"count": "3", "available": "3"},
+                        "total": "100.00"
+                    }
+                ],
+                "total": {"amount": "400.00"}
+            },
+            "currencyCode": "RUB",
+            "orderAmount": "400.00",
+            "orderId": "order"
+        }))
+        .unwrap();
+        let planner = RefundPlanner::new(&order, &[]);
+        assert_eq!(
+            planner
+                .clone()
+                .refund_amount("a", Money::from_rubles(100))
+                .build()
+                .unwrap_err(),
+            RefundPlanError::Indivisible {
+                product_id: "a".into(),
+                total: Money::from_rubles(200),
+                count: 3.0,
+            }
+        );
+        let request = planner
+            .clone()
+            .refund_amount("a", Money::from_rubles(150))
+            .build()
+            .unwrap();
+        assert_eq!(request.refund_amount, Money::from_rubles(150));
+        assert_eq!(
+            request.target_cart.unwrap().items[0].price,
+            Some(Money::from_rubles(50))
+        );
+        assert!(matches!(
+            planner.clone().refund_units("b", 1.0).build(),
+            Err(RefundPlanError::Indivisible { .. })
+        ));
+        let request = planner
+            .refund_amount("b", Money::from_kopecks(1))
+            .build()
+            .unwrap();
+        assert_eq!(request.refund_amount, Money::from_kopecks(1));
+    }
+}
@@ -0,0 +1,227 @@
+use std::time::Duration;
+
+use builder_pattern::Builder;
+
+use crate::*;
+
+/// Политика повторных запросов.
+///
+/// Повторяются только ошибки транспорта, ответы 5xx и 429. POST-запросы повторяются, только если они
+/// идемпотентны (например, содержат `externalOperationId`). При повторе сохраняется `X-Request-Id`,
+/// а `X-Request-Attempt` увеличивается на единицу.
+#[derive(Debug, Clone, Builder)]
+pub struct RetryPolicy {
+    #[default(3)]
+    /// Максимальное количество попыток, включая первую
+    pub max_attempts: u32,
+    #[default(Duration::from_millis(200))]
+    /// Задержка перед первым повтором
+    pub initial_backoff: Duration,
+    #[default(Duration::from_secs(5))]
+    /// Максимальная задержка между попытками
+    pub max_backoff: Duration,
+    #[default(2.0)]
+    /// Множитель задержки для каждой следующей попытки
+    pub multiplier: f64,
+    #[default(true)]
+    /// Добавлять ли случайный разброс к задержке
+    pub jitter: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy::new().build()
+    }
+}
+
+impl RetryPolicy {
+    /// Задержка перед повтором после попытки с номером `attempt` (нумерация с нуля).
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = self
+            .multiplier
+            .max(1.0)
+            .powi(attempt.min(i32::MAX as u32) as i32);
+        let delay = self
+            .initial_backoff
+            .mul_f64(factor.min(u32::MAX as f64))
+            .min(self.max_backoff);
+        if self.jitter {
+            let half = delay / 2;
+            half + half.mul_f64(fastrand::f64())
+        } else {
+            delay
+        }
+    }
+
+    /// Задержка перед повтором запроса после ошибки, `None`, если запрос не повторяется
+    pub(crate) fn next_delay(
+        &self,
+        request: &YandexPayApiRequest,
+        error: &YandexPayApiError,
+    ) -> Option<Duration> {
+        if request.request_attempt + 1 >= self.max_attempts
+            || !request.is_idempotent()
+            || !error.is_retryable()
+        {
+            return None;
+        }
+        let delay = self.backoff(request.request_attempt);
+        tracing::warn!(
+            "Yandex Pay request {} attempt {} failed, retrying in {:?}: {}",
+            request.request_id,
+            request.request_attempt,
+            delay,
+            error
+        );
+        Some(delay)
+    }
+}
+
+/// Подставляет таймаут клиента в запрос, если он задан
+pub(crate) fn apply_timeout(request: &mut YandexPayApiRequest, timeout: Option<Duration>) {
+    if let Some(timeout) = timeout {
+        request.request_timeout = timeout.as_millis().try_into().unwrap_or(u32::MAX);
+    }
+}
+
+impl<C: HttpClient> YandexPayApi<C> {
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(retry_policy);
+        self
+    }
+
+    pub(crate) async fn send<T: serde::de::DeserializeOwned>(
+        &self,
+        mut request: YandexPayApiRequest,
+    ) -> R<T> {
+        apply_timeout(&mut request, self.request_timeout);
+        let Some(policy) = &self.retry_policy else {
+            return self.client.send(request).await;
+        };
+        loop {
+            let result = self.client.send(request.clone()).await;
+            match result
+                .as_ref()
+                .err()
+                .and_then(|err| policy.next_delay(&request, err))
+            {
+                Some(delay) => {
+                    tokio::time::sleep(delay).await;
+                    request.request_attempt += 1;
+                }
+                None => return result,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    #[derive(Clone, Default)]
+    struct FlakyClient {
+        failures: Arc<Mutex<Vec<u32>>>,
+        requests: Arc<Mutex<Vec<YandexPayApiRequest>>>,
+    }
+
+    impl HttpClient for FlakyClient {
+        async fn send<T: serde::de::DeserializeOwned>(&self, request: YandexPayApiRequest) -> R<T> {
+            self.requests.lock().unwrap().push(request);
+            if let Some(code) = self.failures.lock().unwrap().pop() {
+                return Err(YandexPayApiError::Api(YandexPayApiResponseError {
+                    code: Some(code),
+                    status: Some("fail".into()),
+                    ..Default::default()
+                }));
+            }
+            Ok(serde_json::from_value(serde_json::json!({
+                "operation": {
+                    "amount": "10.00",
+                    "operationId": "op",
+                    "operationType": "REFUND",
+                    "orderId": "order",
+                    "status": "PENDING",
+                    "created": null,
+                    "updated": null
+                }
+            }))?)
+        }
+    }
+
+    fn flaky_api(failures: Vec<u32>) -> YandexPayApi<FlakyClient> {
+        let client = FlakyClient::default();
+        *client.failures.lock().unwrap() = failures;
+        YandexPayApi::new("http://localhost".into(), "key".into(), client).with_retry_policy(
+            RetryPolicy::new()
+                .initial_backoff(Duration::from_millis(1))
+                .max_attempts(3)
+                .build(),
+        )
+    }
+
+    fn refund(external_operation_id: Option<&str>) -> RefundRequest {
+        RefundRequest::new()
+            .refund_amount(Money::from_rubles(10))
+            .external_operation_id(external_operation_id.map(Into::into))
+            .build()
+    }
+
+    #[tokio::test]
+    async fn test_retry_keeps_request_id_and_increments_attempt() {
+        let api = flaky_api(vec![503, 429]);
+        api.refund_order("order", refund(Some("ext-1")))
+            .await
+            .unwrap();
+        let requests = api.client.requests.lock().unwrap();
+        assert_eq!(requests.len(), 3);
+        assert!(
+            requests
+                .iter()
+                .all(|r| r.request_id == requests[0].request_id)
+        );
+        let attempts: Vec<u32> = requests.iter().map(|r| r.request_attempt).collect();
+        assert_eq!(attempts, vec![0, 1, 2]);
+    }
+
+    #[tokio::test]
+    async fn test_no_retry_for_non_idempotent_or_client_errors() {
+        let api = flaky_api(vec![503]);
+        assert!(api.refund_order("order", refund(None)).await.is_err());
+        assert_eq!(api.client.requests.lock().unwrap().len(), 1);
+
+        let api = flaky_api(vec![400]);
+        assert!(
+            api.refund_order("order", refund(Some("ext-1")))
+                .await
+                .is_err()
+        );
+        assert_eq!(api.client.requests.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_max_attempts() {
+        let api = flaky_api(vec![500, 500, 500, 500]);
+        assert!(api.get_operation("ext-1").await.is_err());
+        assert_eq!(api.client.requests.lock().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_backoff() {
+        let policy = RetryPolicy::new()
+            .initial_backoff(Duration::from_millis(100))
+            .max_backoff(Duration::from_millis(300))
+            .jitter(false)
+            .build();
+        assert_eq!(policy.backoff(0), Duration::from_millis(100));
+        assert_eq!(policy.backoff(1), Duration::from_millis(200));
+        assert_eq!(policy.backoff(5), Duration::from_millis(300));
+        let jittered = RetryPolicy::new()
+            .initial_backoff(Duration::from_millis(100))
+            .build()
+            .backoff(0);
+        assert!(jittered >= Duration::from_millis(50) && jittered <= Duration::from_millis(100));
+    }
+}
@@ -0,0 +1,103 @@
+use std::fmt;
+
+use zeroize::Zeroize;
+
+use crate::*;
+
+/// Ключ API продавца.
+///
+/// `Debug` и `Display` выводят маску вместо значения, поэтому клиент и запросы можно
+/// логировать через `tracing::debug!(?api)`. Память с ключом обнуляется, когда освобождается
+/// последний клон. Значение используется только в заголовке `Authorization: Api-Key`.
+#[derive(Clone)]
+pub struct SecretKey(Arc<SecretString>);
+
+struct SecretString(String);
+
+impl Drop for SecretString {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl SecretKey {
+    pub fn new(key: impl Into<String>) -> Self {
+        SecretKey(Arc::new(SecretString(key.into())))
+    }
+
+    pub(crate) fn expose(&self) -> &str {
+        &self.0.0
+    }
+
+    /// Значение заголовка `Authorization`
+    pub fn authorization(&self) -> String {
+        format!("Api-Key {}", self.expose())
+    }
+}
+
+impl fmt::Debug for SecretKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SecretKey({self})")
+    }
+}
+
+impl fmt::Display for SecretKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("********")
+    }
+}
+
+impl From<&str> for SecretKey {
+    fn from(key: &str) -> Self {
+        SecretKey::new(key)
+    }
+}
+
+impl From<String> for SecretKey {
+    fn from(key: String) -> Self {
+        SecretKey::new(key)
+    }
+}
+
+impl From<S> for SecretKey {
+    fn from(key: S) -> Self {
+        SecretKey::new(&*key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_client::NoClient;
+
+    #[test]
+    fn test_secret_key_is_masked() {
+        let api = YandexPayApi::new("http://localhost".into(), "top-secret".into(), NoClient);
+        let request = api.endpoints().get_order("order-1").unwrap();
+        for output in [
+            format!("{api:?}"),
+            format!("{request:?}"),
+            format!("{}", api.api_key),
+        ] {
+            assert!(!output.contains("top-secret"), "{output}");
+        }
+        assert!(format!("{request:?}").contains("SecretKey(********)"));
+        assert_eq!(request.headers()[0].1, "Api-Key top-secret");
+    }
+
+    #[cfg(feature = "reqwest")]
+    #[test]
+    fn test_reqwest_authorization_is_sensitive() {
+        let api = YandexPayApi::new("http://localhost".into(), "top-secret".into(), NoClient);
+        let request = api.endpoints().get_order("order-1").unwrap();
+        let headers = reqwest_headers(&request).unwrap();
+        assert!(headers["Authorization"].is_sensitive());
+        assert!(!headers["X-Request-Id"].is_sensitive());
+        let request = reqwest::Client::new()
+            .get(&*request.url)
+            .headers(headers)
+            .build()
+            .unwrap();
+        assert!(!format!("{request:?}").contains("top-secret"));
+    }
+}
@@ -42,12 +42,14 @@ pub mod option_string_as_float {
     }
 }
 
-fn parse_datetime<E: serde::de::Error>(s: &str) -> Result<chrono::DateTime<chrono::Utc>, E> {
-    match chrono::DateTime::parse_from_rfc3339(&s).map(|d| d.to_utc()) {
+pub(crate) fn parse_datetime<E: serde::de::Error>(
+    s: &str,
+) -> Result<chrono::DateTime<chrono::Utc>, E> {
+    match chrono::DateTime::parse_from_rfc3339(s).map(|d| d.to_utc()) {
         Ok(d) => Ok(d),
         Err(err) => {
             if err.kind() == chrono::format::ParseErrorKind::TooShort {
-                let d = chrono::NaiveDate::parse_from_str(&s, "%Y-%m-%d").map_err(|err| {
+                let d = chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d").map_err(|err| {
                     serde::de::Error::custom(format!("Failed to parse date: {}", err))
                 })?;
                 let result = chrono::DateTime::<chrono::Utc>::from_naive_utc_and_offset(
@@ -56,10 +58,10 @@ fn parse_datetime<E: serde::de::Error>(s: &str) -> Result<chrono::DateTime<chron
                 );
                 return Ok(result);
             }
-            return Err(serde::de::Error::custom(format!(
+            Err(serde::de::Error::custom(format!(
                 "Failed to parse date: {}",
                 err
-            )));
+            )))
         }
     }
 }
@@ -104,6 +106,108 @@ pub mod option_iso8601 {
     }
 }
 
+/// Строковое перечисление API с вариантом `Unknown(String)`.
+///
+/// Значения, добавленные Yandex Pay позже выхода крейта, не ломают разбор ответа, а
+/// сохраняются как есть и сериализуются обратно без изменений.
+macro_rules! string_enum {
+    (
+        $(#[$meta:meta])*
+        pub enum $name:ident {
+            $($(#[$variant_meta:meta])* $variant:ident = $value:literal,)*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+        #[serde(from = "String", into = "String")]
+        pub enum $name {
+            $($(#[$variant_meta])* $variant,)*
+            /// Значение, неизвестное этой версии крейта
+            Unknown(String),
+        }
+
+        impl $name {
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $($name::$variant => $value,)*
+                    $name::Unknown(value) => value,
+                }
+            }
+        }
+
+        impl From<String> for $name {
+            fn from(value: String) -> Self {
+                match value.as_str() {
+                    $($value => $name::$variant,)*
+                    _ => $name::Unknown(value),
+                }
+            }
+        }
+
+        impl From<$name> for String {
+            fn from(value: $name) -> Self {
+                match value {
+                    $name::Unknown(value) => value,
+                    value => value.as_str().to_string(),
+                }
+            }
+        }
+
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+    };
+}
+pub(crate) use string_enum;
+
+/// Числовое перечисление API (коды ФНС) с вариантом `Unknown(u32)`.
+///
+/// Как и [`string_enum`], сохраняет новые коды вместо ошибки разбора.
+macro_rules! int_enum {
+    (
+        $(#[$meta:meta])*
+        pub enum $name:ident {
+            $($(#[$variant_meta:meta])* $variant:ident = $value:literal,)*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+        #[serde(from = "u32", into = "u32")]
+        pub enum $name {
+            $($(#[$variant_meta])* $variant,)*
+            /// Код, неизвестный этой версии крейта
+            Unknown(u32),
+        }
+
+        impl $name {
+            pub fn code(&self) -> u32 {
+                match self {
+                    $($name::$variant => $value,)*
+                    $name::Unknown(value) => *value,
+                }
+            }
+        }
+
+        impl From<u32> for $name {
+            fn from(value: u32) -> Self {
+                match value {
+                    $($value => $name::$variant,)*
+                    _ => $name::Unknown(value),
+                }
+            }
+        }
+
+        impl From<$name> for u32 {
+            fn from(value: $name) -> Self {
+                value.code()
+            }
+        }
+    };
+}
+pub(crate) use int_enum;
+
 #[cfg(test)]
 mod tests {
     use crate::Time;
@@ -128,5 +232,13 @@ mod tests {
         }
         let parsed: Test = serde_json::from_str(json).unwrap();
         println!("{:?}", parsed);
+        assert_eq!(
+            parsed.from_date,
+            Some("2022-12-29T00:00:00Z".parse().unwrap())
+        );
+        assert_eq!(
+            parsed.from_date2,
+            "2025-05-11T19:21:09Z".parse::<Time>().unwrap()
+        );
     }
 }
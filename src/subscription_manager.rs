@@ -0,0 +1,624 @@
+//! Расписание и рекуррентные списания по подпискам.
+//!
+//! [`SubscriptionManager`] создаёт подписку, вычисляет даты списаний по
+//! `interval_unit`/`interval_count`/`trial_*` и списывает наступившие периоды через
+//! `recur_subscription`. Хранилище и часы подключаются через трейты
+//! [`SubscriptionStore`] и [`Clock`].
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use chrono::{Months, TimeDelta};
+use serde::{Deserialize, Serialize};
+
+use crate::serde_help::*;
+use crate::*;
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum SubscriptionError {
+    #[error("subscription request has no interval_unit")]
+    MissingInterval,
+    #[error("subscription request has neither future_write_off_amount nor cart")]
+    MissingAmount,
+    #[error("{field} must be positive, got {value}")]
+    InvalidCount { field: &'static str, value: i32 },
+    #[error("invalid trial_end_at {0:?}")]
+    InvalidTrialEnd(String),
+    #[error("subscription {0} is not managed")]
+    NotFound(String),
+}
+
+/// Источник текущего времени
+pub trait Clock {
+    fn now(&self) -> Time;
+}
+
+/// Системные часы
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Time {
+        chrono::Utc::now()
+    }
+}
+
+/// Периодичность списаний подписки
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubscriptionSchedule {
+    pub interval_unit: IntervalUnit,
+    pub interval_count: u32,
+    /// Конец пробного периода, если он задан датой
+    #[serde(default, with = "option_iso8601")]
+    pub trial_end_at: Option<Time>,
+    /// Пробный период, если он задан длительностью
+    #[serde(default)]
+    pub trial: Option<(IntervalUnit, u32)>,
+}
+
+impl SubscriptionSchedule {
+    /// Читает расписание из запроса на создание подписки
+    pub fn from_request(request: &CreateSubscriptionRequest) -> R<Self, SubscriptionError> {
+        let count = |field, count: Option<i32>| match count.unwrap_or(1) {
+            value if value > 0 => Ok(value as u32),
+            value => Err(SubscriptionError::InvalidCount { field, value }),
+        };
+        let trial_end_at = match &request.trial_end_at {
+            Some(value) => Some(
+                parse_datetime::<serde::de::value::Error>(value)
+                    .map_err(|_| SubscriptionError::InvalidTrialEnd(value.clone()))?,
+            ),
+            None => None,
+        };
+        Ok(SubscriptionSchedule {
+            interval_unit: request
+                .interval_unit
+                .clone()
+                .ok_or(SubscriptionError::MissingInterval)?,
+            interval_count: count("interval_count", request.interval_count)?,
+            trial_end_at,
+            trial: match &request.trial_unit {
+                Some(unit) => Some((unit.clone(), count("trial_count", request.trial_count)?)),
+                None => None,
+            },
+        })
+    }
+
+    /// Дата рекуррентного списания номер `period` (с нуля) для подписки, оплаченной в `started`.
+    ///
+    /// Без пробного периода стартовый заказ оплачивает первый интервал, поэтому
+    /// первое списание — через один интервал. Даты считаются от начала подписки, а не от
+    /// предыдущего списания, поэтому месячная подписка от 31 января после февраля
+    /// списывается снова 31 марта.
+    pub fn write_off(&self, started: Time, period: u32) -> Time {
+        let (anchor, offset) = match (&self.trial_end_at, &self.trial) {
+            (Some(trial_end_at), _) => (*trial_end_at, 0),
+            (None, Some((unit, count))) => (add(unit, *count, started), 0),
+            (None, None) => (started, 1),
+        };
+        let intervals = self
+            .interval_count
+            .saturating_mul(period.saturating_add(offset));
+        add(&self.interval_unit, intervals, anchor)
+    }
+}
+
+fn add(unit: &IntervalUnit, count: u32, time: Time) -> Time {
+    let result = match unit {
+        IntervalUnit::Second => time.checked_add_signed(TimeDelta::seconds(count.into())),
+        IntervalUnit::Day => time.checked_add_signed(TimeDelta::days(count.into())),
+        IntervalUnit::Week => time.checked_add_signed(TimeDelta::weeks(count.into())),
+        IntervalUnit::Month => time.checked_add_months(Months::new(count)),
+        IntervalUnit::Year => time.checked_add_months(Months::new(count.saturating_mul(12))),
+    };
+    result.unwrap_or(Time::MAX_UTC)
+}
+
+/// Подписка под управлением [`SubscriptionManager`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManagedSubscription {
+    pub subscription_id: String,
+    /// Идентификатор стартового заказа
+    pub parent_order_id: String,
+    pub schedule: SubscriptionSchedule,
+    /// Сумма рекуррентного списания
+    pub amount: Money,
+    pub cart: RenderedCart,
+    #[serde(default)]
+    pub currency_code: CurrencyCode,
+    /// Дата создания подписки, от которой считаются списания
+    #[serde(with = "iso8601")]
+    pub started: Time,
+    /// Номер следующего списания, начиная с нуля
+    pub period: u32,
+    /// Номер попытки списания текущего периода, начиная с нуля. Растёт, когда заказ
+    /// предыдущей попытки отклонён.
+    #[serde(default)]
+    pub attempt: u32,
+    #[serde(with = "iso8601")]
+    pub next_write_off: Time,
+    /// Последний известный статус подписки
+    pub status: SubscriptionStatus,
+}
+
+impl ManagedSubscription {
+    /// Подписка отменена или истекла, списаний больше не будет
+    pub fn is_finished(&self) -> bool {
+        matches!(
+            self.status,
+            SubscriptionStatus::Cancelled | SubscriptionStatus::Expired
+        )
+    }
+
+    /// Идентификатор заказа для текущего периода и попытки.
+    ///
+    /// Повторное списание той же попытки вернёт `ORDER_ALREADY_EXISTS` вместо второго платежа.
+    /// Попытки после отклонённой оплаты получают суффикс `-attempt-N`.
+    pub fn charge_order_id(&self) -> String {
+        let order_id = format!("{}-recur-{}", self.parent_order_id, self.period + 1);
+        match self.attempt {
+            0 => order_id,
+            attempt => format!("{order_id}-attempt-{}", attempt + 1),
+        }
+    }
+}
+
+/// Хранилище подписок
+pub trait SubscriptionStore {
+    fn load(&self, subscription_id: &str) -> impl Future<Output = R<Option<ManagedSubscription>>>;
+
+    fn save(&self, subscription: &ManagedSubscription) -> impl Future<Output = R>;
+
+    /// Незавершённые подписки, у которых `next_write_off` не позже `now`
+    fn due(&self, now: Time) -> impl Future<Output = R<Vec<ManagedSubscription>>>;
+}
+
+/// Хранилище в памяти. Клоны разделяют одно и то же состояние.
+#[derive(Debug, Clone, Default)]
+pub struct InMemorySubscriptionStore {
+    subscriptions: Arc<Mutex<HashMap<String, ManagedSubscription>>>,
+}
+
+impl SubscriptionStore for InMemorySubscriptionStore {
+    async fn load(&self, subscription_id: &str) -> R<Option<ManagedSubscription>> {
+        Ok(self
+            .subscriptions
+            .lock()
+            .unwrap()
+            .get(subscription_id)
+            .cloned())
+    }
+
+    async fn save(&self, subscription: &ManagedSubscription) -> R {
+        self.subscriptions
+            .lock()
+            .unwrap()
+            .insert(subscription.subscription_id.clone(), subscription.clone());
+        Ok(())
+    }
+
+    async fn due(&self, now: Time) -> R<Vec<ManagedSubscription>> {
+        let subscriptions = self.subscriptions.lock().unwrap();
+        let mut due: Vec<_> = subscriptions
+            .values()
+            .filter(|s| !s.is_finished() && s.next_write_off <= now)
+            .cloned()
+            .collect();
+        due.sort_by_key(|s| s.next_write_off);
+        Ok(due)
+    }
+}
+
+/// Результат обработки одной подписки в [`SubscriptionManager::charge_due`]
+#[derive(Debug)]
+pub enum ChargeResult {
+    /// Списание выполнено. `operation_id` пустой, если заказ периода уже существовал
+    /// и его оплата не отклонена.
+    Charged {
+        order_id: String,
+        operation_id: Option<String>,
+    },
+    /// Срок следующего списания ещё не наступил
+    NotDue(Time),
+    /// Карта покупателя неактивна, списание повторится при следующем запуске
+    CardInactive,
+    /// Подписка не активна. Для `CANCELLED` и `EXPIRED` списания прекращаются.
+    NotActive(SubscriptionStatus),
+    /// Ошибка API, списание повторится при следующем запуске
+    Failed(YandexPayApiError),
+}
+
+#[derive(Debug)]
+pub struct ChargeOutcome {
+    pub subscription_id: String,
+    pub result: ChargeResult,
+}
+
+/// Создаёт подписки и списывает наступившие периоды.
+///
+/// `charge_due` удобно вызывать по расписанию (cron, `tokio::time::interval`). Заказ
+/// каждого периода получает детерминированный идентификатор, поэтому повторный запуск
+/// после сбоя не спишет период дважды. Если оплата заказа периода отклонена, следующая
+/// попытка создаёт новый заказ с суффиксом `-attempt-N`. Пропущенные периоды не списываются задним
+/// числом: после списания дата переносится на ближайший будущий период.
+#[derive(Debug, Clone)]
+pub struct SubscriptionManager<C: HttpClient, St: SubscriptionStore, Cl: Clock = SystemClock> {
+    pub api: YandexPayApi<C>,
+    pub store: St,
+    pub clock: Cl,
+}
+
+impl<C: HttpClient, St: SubscriptionStore> SubscriptionManager<C, St> {
+    pub fn new(api: YandexPayApi<C>, store: St) -> Self {
+        SubscriptionManager {
+            api,
+            store,
+            clock: SystemClock,
+        }
+    }
+}
+
+impl<C: HttpClient, St: SubscriptionStore, Cl: Clock> SubscriptionManager<C, St, Cl> {
+    pub fn with_clock<Cl2: Clock>(self, clock: Cl2) -> SubscriptionManager<C, St, Cl2> {
+        SubscriptionManager {
+            api: self.api,
+            store: self.store,
+            clock,
+        }
+    }
+
+    /// Создаёт подписку и сохраняет её расписание.
+    ///
+    /// Сумма списаний — `future_write_off_amount`, а если она не задана, — сумма корзины.
+    pub async fn create(
+        &self,
+        request: CreateSubscriptionRequest,
+    ) -> R<(CreateSubscriptionResponseData, ManagedSubscription)> {
+        let schedule = SubscriptionSchedule::from_request(&request)?;
+        let amount = request
+            .future_write_off_amount
+            .or_else(|| request.cart.as_ref().map(|cart| cart.total.amount))
+            .ok_or(SubscriptionError::MissingAmount)?;
+        let cart = request.cart.clone().unwrap_or_else(|| {
+            RenderedCart::new()
+                .items(vec![])
+                .total(CartTotal::new().amount(amount).build())
+                .external_id(request.order_id.clone())
+                .build()
+        });
+        let parent_order_id = request.order_id.clone();
+        let currency_code = request.currency_code.clone();
+        let response = self.api.create_subscription(request).await?;
+        let started = self.clock.now();
+        let next_write_off = schedule.write_off(started, 0);
+        let subscription = ManagedSubscription {
+            subscription_id: response.subscription_id.clone(),
+            parent_order_id,
+            schedule,
+            amount,
+            cart,
+            currency_code,
+            started,
+            period: 0,
+            attempt: 0,
+            next_write_off,
+            status: SubscriptionStatus::New,
+        };
+        self.store.save(&subscription).await?;
+        Ok((response, subscription))
+    }
+
+    /// Списывает все подписки, срок которых наступил.
+    ///
+    /// Ошибки API по отдельной подписке возвращаются в [`ChargeResult::Failed`], ошибки
+    /// хранилища прерывают обработку.
+    pub async fn charge_due(&self) -> R<Vec<ChargeOutcome>> {
+        let now = self.clock.now();
+        let mut outcomes = Vec::new();
+        for subscription in self.store.due(now).await? {
+            let subscription_id = subscription.subscription_id.clone();
+            let result = self.charge(subscription, now).await?;
+            outcomes.push(ChargeOutcome {
+                subscription_id,
+                result,
+            });
+        }
+        Ok(outcomes)
+    }
+
+    /// Списывает одну подписку, если её срок наступил
+    pub async fn charge_subscription(&self, subscription_id: &str) -> R<ChargeOutcome> {
+        let subscription = self
+            .store
+            .load(subscription_id)
+            .await?
+            .ok_or_else(|| SubscriptionError::NotFound(subscription_id.to_string()))?;
+        let result = self.charge(subscription, self.clock.now()).await?;
+        Ok(ChargeOutcome {
+            subscription_id: subscription_id.to_string(),
+            result,
+        })
+    }
+
+    async fn charge(&self, mut subscription: ManagedSubscription, now: Time) -> R<ChargeResult> {
+        if subscription.is_finished() {
+            return Ok(ChargeResult::NotActive(subscription.status));
+        }
+        if subscription.next_write_off > now {
+            return Ok(ChargeResult::NotDue(subscription.next_write_off));
+        }
+        let state = match self
+            .api
+            .get_subscription(
+                &*subscription.subscription_id,
+                GetSubscriptionRequest::new()
+                    .check_card_active(true)
+                    .build(),
+            )
+            .await
+        {
+            Ok(state) => state,
+            Err(err) => return Ok(ChargeResult::Failed(err)),
+        };
+        if state.status != subscription.status {
+            subscription.status = state.status.clone();
+            self.store.save(&subscription).await?;
+        }
+        if state.status != SubscriptionStatus::Active {
+            return Ok(ChargeResult::NotActive(state.status));
+        }
+        if !state.is_card_active {
+            return Ok(ChargeResult::CardInactive);
+        }
+
+        let (order_id, operation_id) = loop {
+            let order_id = subscription.charge_order_id();
+            let request = CreateRecurrentChargeRequest::new()
+                .amount(subscription.amount)
+                .cart(subscription.cart.clone())
+                .currency_code(subscription.currency_code.clone())
+                .order_id(order_id.clone())
+                .parent_order_id(subscription.parent_order_id.clone())
+                .build();
+            match self.api.recur_subscription(request).await {
+                Ok(response) => break (order_id, Some(response.operation_id)),
+                Err(err) if err.reason_code() == Some(&ReasonCode::OrderAlreadyExists) => {
+                    // Заказ создан прошлым запуском, но его оплата могла быть отклонена
+                    match self.api.get_order(order_id.clone()).await {
+                        Ok(data) if is_failed(&data) => {
+                            subscription.attempt += 1;
+                            self.store.save(&subscription).await?;
+                        }
+                        Ok(_) => break (order_id, None),
+                        Err(err) => return Ok(ChargeResult::Failed(err)),
+                    }
+                }
+                Err(err) => return Ok(ChargeResult::Failed(err)),
+            }
+        };
+
+        let schedule = &subscription.schedule;
+        let mut period = subscription.period + 1;
+        while schedule.write_off(subscription.started, period) <= now {
+            period += 1;
+        }
+        subscription.period = period;
+        subscription.attempt = 0;
+        subscription.next_write_off = schedule.write_off(subscription.started, period);
+        self.store.save(&subscription).await?;
+        Ok(ChargeResult::Charged {
+            order_id,
+            operation_id,
+        })
+    }
+}
+
+fn is_failed(data: &OrderResponseData) -> bool {
+    let status = data
+        .order
+        .as_ref()
+        .and_then(|order| order.payment_status.as_ref());
+    status == Some(&PaymentStatus::Failed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(value: &str) -> Time {
+        value.parse().unwrap()
+    }
+
+    fn request() -> CreateSubscriptionRequest {
+        let cart = RenderedCart::new()
+            .items(vec![
+                RenderedCartItem::new()
+                    .product_id("plan")
+                    .quantity(ItemQuantity::new().count(1.0).available(1.0).build())
+                    .title("Подписка")
+                    .total(Money::from_rubles(100))
+                    .build(),
+            ])
+            .total(CartTotal::new().amount(Money::from_rubles(100)).build())
+            .external_id("plan")
+            .build();
+        CreateSubscriptionRequest::new()
+            .order_id("start")
+            .cart(Some(cart))
+            .interval_unit(Some(IntervalUnit::Month))
+            .interval_count(Some(1))
+            .build()
+    }
+
+    #[test]
+    fn test_schedule() {
+        let schedule = SubscriptionSchedule::from_request(&request()).unwrap();
+        let started = time("2025-01-31T10:00:00Z");
+        assert_eq!(schedule.write_off(started, 0), time("2025-02-28T10:00:00Z"));
+        assert_eq!(schedule.write_off(started, 1), time("2025-03-31T10:00:00Z"));
+
+        let mut request = request();
+        request.trial_unit = Some(IntervalUnit::Day);
+        request.trial_count = Some(7);
+        let schedule = SubscriptionSchedule::from_request(&request).unwrap();
+        assert_eq!(schedule.write_off(started, 0), time("2025-02-07T10:00:00Z"));
+        assert_eq!(schedule.write_off(started, 1), time("2025-03-07T10:00:00Z"));
+
+        request.trial_end_at = Some("2025-03-01".into());
+        let schedule = SubscriptionSchedule::from_request(&request).unwrap();
+        assert_eq!(schedule.write_off(started, 0), time("2025-03-01T00:00:00Z"));
+
+        request.trial_count = Some(0);
+        assert_eq!(
+            SubscriptionSchedule::from_request(&request),
+            Err(SubscriptionError::InvalidCount {
+                field: "trial_count",
+                value: 0
+            })
+        );
+        request.trial_count = Some(7);
+        request.interval_count = Some(-1);
+        assert_eq!(
+            SubscriptionSchedule::from_request(&request),
+            Err(SubscriptionError::InvalidCount {
+                field: "interval_count",
+                value: -1
+            })
+        );
+
+        request.interval_unit = None;
+        assert_eq!(
+            SubscriptionSchedule::from_request(&request),
+            Err(SubscriptionError::MissingInterval)
+        );
+    }
+
+    #[cfg(feature = "testing")]
+    #[tokio::test]
+    async fn test_create_without_amount() {
+        let fake = FakeYandexPay::new();
+        let api = YandexPayApi::new("http://localhost".into(), "key".into(), fake.clone());
+        let manager = SubscriptionManager::new(api, InMemorySubscriptionStore::default());
+        let mut request = request();
+        request.cart = None;
+        let err = manager.create(request).await.unwrap_err();
+        assert!(matches!(
+            err,
+            YandexPayApiError::Subscription(SubscriptionError::MissingAmount)
+        ));
+        assert!(fake.requests().is_empty());
+    }
+
+    #[cfg(feature = "testing")]
+    #[tokio::test]
+    async fn test_charge_due() {
+        #[derive(Debug, Clone)]
+        struct FakeClock(Arc<Mutex<Time>>);
+
+        impl FakeClock {
+            fn set(&self, value: &str) {
+                *self.0.lock().unwrap() = time(value);
+            }
+        }
+
+        impl Clock for FakeClock {
+            fn now(&self) -> Time {
+                *self.0.lock().unwrap()
+            }
+        }
+
+        let fake = FakeYandexPay::new();
+        let api = YandexPayApi::new("http://localhost".into(), "key".into(), fake.clone());
+        let clock = FakeClock(Arc::new(Mutex::new(time("2025-01-31T10:00:00Z"))));
+        let store = InMemorySubscriptionStore::default();
+        let manager = SubscriptionManager::new(api, store.clone()).with_clock(clock.clone());
+
+        let (created, managed) = manager.create(request()).await.unwrap();
+        let id = created.subscription_id;
+        assert_eq!(managed.next_write_off, time("2025-02-28T10:00:00Z"));
+        assert!(manager.charge_due().await.unwrap().is_empty());
+        assert!(matches!(
+            manager.charge_subscription(&id).await.unwrap().result,
+            ChargeResult::NotDue(_)
+        ));
+
+        clock.set("2025-03-01T00:00:00Z");
+        let outcomes = manager.charge_due().await.unwrap();
+        assert!(matches!(
+            outcomes[0].result,
+            ChargeResult::NotActive(SubscriptionStatus::New)
+        ));
+
+        fake.set_payment_status("start", PaymentStatus::Captured);
+        fake.set_card_active(&id, false);
+        let outcomes = manager.charge_due().await.unwrap();
+        assert!(matches!(outcomes[0].result, ChargeResult::CardInactive));
+
+        fake.set_card_active(&id, true);
+        let before_charge = store.load(&id).await.unwrap().unwrap();
+        let outcomes = manager.charge_due().await.unwrap();
+        let ChargeResult::Charged {
+            order_id,
+            operation_id,
+        } = &outcomes[0].result
+        else {
+            panic!("{outcomes:?}");
+        };
+        assert_eq!(order_id, "start-recur-1");
+        assert!(operation_id.is_some());
+        let charged = store.load(&id).await.unwrap().unwrap();
+        assert_eq!(charged.status, SubscriptionStatus::Active);
+        assert_eq!(charged.next_write_off, time("2025-03-31T10:00:00Z"));
+        assert!(manager.charge_due().await.unwrap().is_empty());
+
+        // Сбой после списания, но до сохранения: период не списывается повторно
+        store.save(&before_charge).await.unwrap();
+        let outcomes = manager.charge_due().await.unwrap();
+        assert!(matches!(
+            &outcomes[0].result,
+            ChargeResult::Charged { order_id, operation_id: None } if order_id == "start-recur-1"
+        ));
+        assert_eq!(
+            fake.order("start-recur-1").unwrap().order.order_amount,
+            Money::from_rubles(100)
+        );
+
+        // Оплата заказа периода отклонена: период списывается новым заказом
+        store.save(&before_charge).await.unwrap();
+        fake.set_payment_status("start-recur-1", PaymentStatus::Failed);
+        let outcomes = manager.charge_due().await.unwrap();
+        let ChargeResult::Charged {
+            order_id,
+            operation_id,
+        } = &outcomes[0].result
+        else {
+            panic!("{outcomes:?}");
+        };
+        assert_eq!(order_id, "start-recur-1-attempt-2");
+        assert!(operation_id.is_some());
+        let charged = store.load(&id).await.unwrap().unwrap();
+        assert_eq!((charged.period, charged.attempt), (1, 0));
+        assert_eq!(charged.next_write_off, time("2025-03-31T10:00:00Z"));
+
+        // Пропущенные периоды не списываются задним числом
+        clock.set("2025-06-15T00:00:00Z");
+        let outcomes = manager.charge_due().await.unwrap();
+        assert_eq!(outcomes.len(), 1);
+        let charged = store.load(&id).await.unwrap().unwrap();
+        assert_eq!(charged.next_write_off, time("2025-06-30T10:00:00Z"));
+        assert!(fake.order("start-recur-2").is_some());
+        assert!(fake.order("start-recur-3").is_none());
+
+        fake.set_subscription_status(&id, SubscriptionStatus::Cancelled);
+        clock.set("2025-07-01T00:00:00Z");
+        let outcomes = manager.charge_due().await.unwrap();
+        assert!(matches!(
+            outcomes[0].result,
+            ChargeResult::NotActive(SubscriptionStatus::Cancelled)
+        ));
+        assert!(store.load(&id).await.unwrap().unwrap().is_finished());
+        assert!(manager.charge_due().await.unwrap().is_empty());
+    }
+}
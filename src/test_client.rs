@@ -0,0 +1,13 @@
+//! Общие клиенты для модульных тестов.
+
+use crate::*;
+
+/// Клиент для тестов, в которых запрос не должен дойти до сети
+#[derive(Debug, Clone)]
+pub(crate) struct NoClient;
+
+impl HttpClient for NoClient {
+    async fn send<T: serde::de::DeserializeOwned>(&self, _: YandexPayApiRequest) -> R<T> {
+        unreachable!("request must not be sent")
+    }
+}
@@ -0,0 +1,1058 @@
+//! Фейковый бэкенд Yandex Pay для тестов без сети.
+//!
+//! [`FakeYandexPay`] реализует [`HttpClient`] и [`RawHttpClient`], хранит заказы, операции
+//! и подписки в памяти и применяет документированные переходы статусов.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+use crate::*;
+
+/// Заказ, хранящийся в фейковом бэкенде
+#[derive(Debug, Clone)]
+pub struct FakeOrder {
+    pub order: BaseMerchantApiOrder,
+    pub operations: Vec<Operation>,
+}
+
+/// Подписка, хранящаяся в фейковом бэкенде
+#[derive(Debug, Clone)]
+pub struct FakeSubscription {
+    pub subscription: CustomerSubscriptionResponseData,
+    /// Идентификатор стартового заказа
+    pub order_id: String,
+    /// Заказы рекуррентных списаний, от самого раннего
+    pub charge_order_ids: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+struct FakeState {
+    orders: HashMap<String, FakeOrder>,
+    subscriptions: HashMap<String, FakeSubscription>,
+    /// Возвраты, которые применятся к заказу после успеха операции, по `operationId`
+    pending_refunds: HashMap<String, (String, RefundRequest)>,
+    requests: Vec<YandexPayApiRequest>,
+}
+
+/// Хранящий состояние в памяти [`HttpClient`], имитирующий Yandex Pay API.
+///
+/// Клоны разделяют одно и то же состояние.
+#[derive(Debug, Clone, Default)]
+pub struct FakeYandexPay {
+    state: Arc<Mutex<FakeState>>,
+}
+
+impl FakeYandexPay {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Все запросы, полученные фейковым бэкендом
+    pub fn requests(&self) -> Vec<YandexPayApiRequest> {
+        self.lock().requests.clone()
+    }
+
+    pub fn order(&self, order_id: &str) -> Option<FakeOrder> {
+        self.lock().orders.get(order_id).cloned()
+    }
+
+    pub fn subscription(&self, subscription_id: &str) -> Option<FakeSubscription> {
+        self.lock().subscriptions.get(subscription_id).cloned()
+    }
+
+    /// Имитирует действие покупателя или банка, меняя статус оплаты заказа.
+    ///
+    /// Например, `AUTHORIZED` после оплаты двухстадийного заказа.
+    pub fn set_payment_status(&self, order_id: &str, status: PaymentStatus) -> bool {
+        let mut state = self.lock();
+        let Some(order) = state.orders.get_mut(order_id) else {
+            return false;
+        };
+        order.order.payment_status = Some(status.clone());
+        order.order.updated = Some(chrono::Utc::now());
+        if matches!(status, PaymentStatus::Captured | PaymentStatus::Authorized) {
+            let subscription = state
+                .subscriptions
+                .values_mut()
+                .find(|s| s.order_id == order_id);
+            if let Some(subscription) = subscription {
+                subscription.subscription.status = SubscriptionStatus::Active;
+            }
+        }
+        true
+    }
+
+    /// Меняет статус операции, найденной по `operationId` или `externalOperationId`.
+    ///
+    /// Как и в API, возврат меняет корзину, сумму и статус оплаты заказа только после
+    /// перехода операции в `SUCCESS`.
+    pub fn set_operation_status(&self, operation_id: &str, status: OperationStatus) -> bool {
+        let mut state = self.lock();
+        let operation = state
+            .orders
+            .values_mut()
+            .flat_map(|order| order.operations.iter_mut())
+            .find(|op| {
+                op.operation_id == operation_id
+                    || op.external_operation_id.as_deref() == Some(operation_id)
+            });
+        let Some(operation) = operation else {
+            return false;
+        };
+        operation.status = status.clone();
+        operation.updated = Some(chrono::Utc::now());
+        let operation_id = operation.operation_id.clone();
+        if status.is_terminal()
+            && let Some((order_id, request)) = state.pending_refunds.remove(&operation_id)
+            && status == OperationStatus::Success
+            && let Some(order) = state.orders.get_mut(&order_id)
+        {
+            apply_refund(&mut order.order, &request);
+        }
+        true
+    }
+
+    pub fn set_subscription_status(
+        &self,
+        subscription_id: &str,
+        status: SubscriptionStatus,
+    ) -> bool {
+        let mut state = self.lock();
+        match state.subscriptions.get_mut(subscription_id) {
+            Some(subscription) => {
+                if status == SubscriptionStatus::Cancelled {
+                    subscription.subscription.cancelled_at = Some(chrono::Utc::now());
+                }
+                subscription.subscription.status = status;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Имитирует истёкшую или заблокированную карту покупателя
+    pub fn set_card_active(&self, subscription_id: &str, is_card_active: bool) -> bool {
+        let mut state = self.lock();
+        match state.subscriptions.get_mut(subscription_id) {
+            Some(subscription) => {
+                subscription.subscription.is_card_active = is_card_active;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, FakeState> {
+        self.state.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    fn handle(&self, request: &YandexPayApiRequest) -> R<serde_json::Value> {
+        let path = request
+            .url
+            .split_once("/api/merchant/")
+            .map(|(_, path)| path)
+            .unwrap_or(&request.url);
+        let path = path.split_once('?').map(|(path, _)| path).unwrap_or(path);
+        let segments: Vec<&str> = path.split('/').collect();
+        let mut state = self.lock();
+        state.requests.push(request.clone());
+        match (request.method, segments.as_slice()) {
+            (Method::Post, ["v1", "orders"]) => state.create_order(body(request)?),
+            (Method::Get, ["v1", "orders", id]) => state.get_order(id),
+            (Method::Post, ["v1", "orders", id, "cancel"]) => state.cancel(id, body(request)?),
+            (Method::Post, ["v2", "orders", id, "refund"]) => state.refund(id, body(request)?),
+            (Method::Post, ["v1", "orders", id, "capture"]) => state.capture(id, body(request)?),
+            (Method::Post, ["v1", "orders", id, "rollback"]) => state.rollback(id),
+            (Method::Post, ["v1", "orders", id, "submit"]) => state.submit(id, body(request)?),
+            (Method::Get, ["v1", "operations", id]) => state.get_operation(id),
+            (Method::Post, ["v1", "subscriptions"]) => state.create_subscription(body(request)?),
+            (Method::Post, ["v1", "subscriptions", "recur"]) => state.recur(body(request)?),
+            (Method::Get, ["v1", "subscriptions", id]) => state.get_subscription(id),
+            (Method::Post, ["v1", "subscriptions", id, "cancel"]) => state.cancel_subscription(id),
+            (Method::Get, ["v1", "subscriptions", id, "charges"]) => {
+                state.subscription_charges(id, &request.query)
+            }
+            _ => Err(api_error(404, "NOT_FOUND", "Unknown endpoint")),
+        }
+    }
+}
+
+impl HttpClient for FakeYandexPay {
+    async fn send<T: DeserializeOwned>(&self, request: YandexPayApiRequest) -> R<T> {
+        let data = self.handle(&request)?;
+        let envelope = serde_json::json!({ "status": "success", "code": 200, "data": data });
+        let response: YandexPayApiResponse<T> = serde_json::from_value(envelope)?;
+        Ok(response.data)
+    }
+}
+
+impl RawHttpClient for FakeYandexPay {
+    fn send_raw(&self, request: YandexPayApiRequest) -> RawFuture<'_> {
+        let response = match self.handle(&request) {
+            Ok(data) => Ok((
+                200,
+                serde_json::json!({ "status": "success", "code": 200, "data": data }),
+            )),
+            Err(YandexPayApiError::Api(error)) => Ok((
+                error.http_status.unwrap_or(500),
+                serde_json::json!({
+                    "status": "fail",
+                    "code": error.code,
+                    "message": error.message,
+                    "reasonCode": error.reason_code,
+                    "reason": error.reason,
+                }),
+            )),
+            Err(err) => Err(err),
+        };
+        Box::pin(async move {
+            let (status, envelope) = response?;
+            Ok(RawResponse {
+                status,
+                body: serde_json::to_vec(&envelope)?.into(),
+            })
+        })
+    }
+}
+
+fn body<T: DeserializeOwned>(request: &YandexPayApiRequest) -> R<T> {
+    let body = request.body.as_deref().unwrap_or(b"{}");
+    serde_json::from_slice(body).map_err(|err| api_error(400, "BAD_REQUEST", &err.to_string()))
+}
+
+fn api_error(code: u32, reason_code: &str, reason: &str) -> YandexPayApiError {
+    let envelope = serde_json::json!({
+        "status": "fail",
+        "code": code,
+        "reasonCode": reason_code,
+        "reason": reason,
+    });
+    match serde_json::from_value::<YandexPayApiResponseError>(envelope) {
+        Ok(error) => YandexPayApiError::Api(YandexPayApiResponseError {
+            http_status: Some(code as u16),
+            ..error
+        }),
+        Err(err) => YandexPayApiError::Serde(err),
+    }
+}
+
+fn to_value<T: Serialize>(value: T) -> R<serde_json::Value> {
+    Ok(serde_json::to_value(value)?)
+}
+
+fn new_id() -> String {
+    uuid::Uuid::now_v7().to_string()
+}
+
+fn order_not_found() -> YandexPayApiError {
+    api_error(404, "ORDER_NOT_FOUND", "Order not found")
+}
+
+fn invalid_status(status: &Option<PaymentStatus>) -> YandexPayApiError {
+    api_error(
+        409,
+        "ORDER_HAS_INVALID_STATUS",
+        &format!("Operation is not allowed for payment status {status:?}"),
+    )
+}
+
+fn cart_from_rendered(cart: &RenderedCart) -> Cart {
+    Cart {
+        items: cart
+            .items
+            .iter()
+            .map(|item| CartItem {
+                product_id: item.product_id.clone(),
+                quantity: item.quantity.clone(),
+                description: item.description.clone(),
+                discounted_unit_price: item.discounted_unit_price,
+                features: item.features.clone(),
+                final_price: None,
+                measurements: None,
+                points_amount: item.points_amount,
+                receipt: item.receipt.clone(),
+                subtotal: item.subtotal,
+                title: Some(item.title.clone()),
+                total: Some(item.total),
+                item_type: None,
+                unit_price: item.unit_price,
+            })
+            .collect(),
+        cart_id: new_id(),
+        coupons: vec![],
+        discounts: vec![],
+        external_id: Some(cart.external_id.clone()),
+        measurements: None,
+        total: cart.total.clone(),
+    }
+}
+
+fn cart_from_final(cart: &CartWithRequiredTotalWithoutFinalPrice, cart_id: String) -> Cart {
+    Cart {
+        items: cart
+            .items
+            .iter()
+            .map(|item| CartItem {
+                product_id: item.product_id.clone(),
+                quantity: item.quantity.clone(),
+                description: item.description.clone(),
+                discounted_unit_price: item.discounted_unit_price,
+                features: item.features.clone(),
+                final_price: None,
+                measurements: item.measurements.clone(),
+                points_amount: item.points_amount,
+                receipt: item.receipt.clone(),
+                subtotal: item.subtotal,
+                title: item.title.clone(),
+                total: item
+                    .discounted_unit_price
+                    .or(item.unit_price)
+                    .map(|price| price.mul_quantity(item.quantity.count)),
+                item_type: item.item_type.clone(),
+                unit_price: item.unit_price,
+            })
+            .collect(),
+        cart_id: cart.cart_id.clone().unwrap_or(cart_id),
+        coupons: cart.coupons.clone(),
+        discounts: cart.discounts.clone(),
+        external_id: cart.external_id.clone(),
+        measurements: cart.measurements.clone(),
+        total: cart.total.clone(),
+    }
+}
+
+fn unit_price(item: &CartItem) -> Option<Money> {
+    item.final_price
+        .or(item.discounted_unit_price)
+        .or(item.unit_price)
+}
+
+impl FakeState {
+    fn insert_order(&mut self, order_id: String, cart: &RenderedCart, metadata: Option<String>) {
+        let now = chrono::Utc::now();
+        let order = BaseMerchantApiOrder {
+            cart: cart_from_rendered(cart),
+            currency_code: CurrencyCode::Rub,
+            created: Some(now),
+            is_prepayment: false,
+            merchant_id: None,
+            metadata,
+            order_amount: cart.total.amount,
+            order_id: order_id.clone(),
+            payment_method: None,
+            payment_status: Some(PaymentStatus::Pending),
+            payment_url: Some(format!("https://sandbox.pay.yandex.ru/l/{}", new_id())),
+            reason: None,
+            shipping_method: None,
+            updated: Some(now),
+        };
+        self.orders.insert(
+            order_id,
+            FakeOrder {
+                order,
+                operations: vec![],
+            },
+        );
+    }
+
+    fn order_mut(&mut self, order_id: &str) -> R<&mut FakeOrder> {
+        self.orders.get_mut(order_id).ok_or_else(order_not_found)
+    }
+
+    fn create_order(&mut self, request: CreateOrderRequest) -> R<serde_json::Value> {
+        if self.orders.contains_key(&request.order_id) {
+            return Err(api_error(
+                409,
+                "ORDER_ALREADY_EXISTS",
+                "Order already exists",
+            ));
+        }
+        self.insert_order(request.order_id.clone(), &request.cart, request.metadata);
+        let order = &self.orders[&request.order_id].order;
+        to_value(serde_json::json!({ "paymentUrl": order.payment_url }))
+    }
+
+    fn get_order(&mut self, order_id: &str) -> R<serde_json::Value> {
+        let order = self.orders.get(order_id).ok_or_else(order_not_found)?;
+        let operations: Vec<serde_json::Value> =
+            order.operations.iter().map(to_value).collect::<R<_>>()?;
+        to_value(serde_json::json!({
+            "delivery": null,
+            "operations": operations,
+            "order": order.order,
+        }))
+    }
+
+    /// Возвращает уже существующую операцию для повторного запроса с тем же `externalOperationId`.
+    fn existing_operation(
+        &self,
+        order_id: &str,
+        operation_type: &OperationType,
+        external_operation_id: Option<&str>,
+    ) -> R<Option<Operation>> {
+        let Some(external_operation_id) = external_operation_id else {
+            return Ok(None);
+        };
+        let existing = self
+            .orders
+            .values()
+            .flat_map(|order| order.operations.iter())
+            .find(|op| op.external_operation_id.as_deref() == Some(external_operation_id));
+        match existing {
+            Some(op) if op.order_id == order_id && op.operation_type == *operation_type => {
+                Ok(Some(op.clone()))
+            }
+            Some(_) => Err(api_error(
+                409,
+                "DUPLICATE_EXTERNAL_OPERATION_ID",
+                "Operation with this externalOperationId already exists",
+            )),
+            None => Ok(None),
+        }
+    }
+
+    fn push_operation(
+        &mut self,
+        order_id: &str,
+        operation_type: OperationType,
+        amount: Money,
+        status: OperationStatus,
+        external_operation_id: Option<String>,
+    ) -> R<serde_json::Value> {
+        let now = chrono::Utc::now();
+        let operation = Operation {
+            amount,
+            operation_id: new_id(),
+            operation_type,
+            order_id: order_id.to_string(),
+            created: Some(now),
+            external_operation_id,
+            params: None,
+            reason: None,
+            status,
+            updated: Some(now),
+        };
+        let order = self.order_mut(order_id)?;
+        order.operations.push(operation.clone());
+        order.order.updated = Some(now);
+        to_value(serde_json::json!({ "operation": operation }))
+    }
+
+    fn cancel(&mut self, order_id: &str, request: CancelOrderRequest) -> R<serde_json::Value> {
+        let external_id = request.external_operation_id.as_deref();
+        if let Some(op) = self.existing_operation(order_id, &OperationType::Void, external_id)? {
+            return to_value(serde_json::json!({ "operation": op }));
+        }
+        let order = &mut self.order_mut(order_id)?.order;
+        if order.payment_status != Some(PaymentStatus::Authorized) {
+            return Err(invalid_status(&order.payment_status));
+        }
+        order.payment_status = Some(PaymentStatus::Voided);
+        order.reason = Some(request.reason);
+        let amount = order.order_amount;
+        self.push_operation(
+            order_id,
+            OperationType::Void,
+            amount,
+            OperationStatus::Success,
+            request.external_operation_id,
+        )
+    }
+
+    fn capture(&mut self, order_id: &str, request: CaptureOrderRequest) -> R<serde_json::Value> {
+        let external_id = request.external_operation_id.as_deref();
+        if let Some(op) = self.existing_operation(order_id, &OperationType::Capture, external_id)? {
+            return to_value(serde_json::json!({ "operation": op }));
+        }
+        let order = &mut self.order_mut(order_id)?.order;
+        if order.payment_status != Some(PaymentStatus::Authorized) {
+            return Err(invalid_status(&order.payment_status));
+        }
+        let amount = request
+            .order_amount
+            .or(request.cart.as_ref().map(|cart| cart.total.amount))
+            .unwrap_or(order.order_amount);
+        if amount > order.order_amount {
+            return Err(api_error(
+                400,
+                "AMOUNT_MISMATCH",
+                "Capture amount exceeds order amount",
+            ));
+        }
+        if let Some(cart) = &request.cart {
+            order.cart = cart_from_final(cart, order.cart.cart_id.clone());
+        }
+        order.order_amount = amount;
+        order.payment_status = Some(PaymentStatus::Captured);
+        self.push_operation(
+            order_id,
+            OperationType::Capture,
+            amount,
+            OperationStatus::Success,
+            request.external_operation_id,
+        )
+    }
+
+    fn refund(&mut self, order_id: &str, request: RefundRequest) -> R<serde_json::Value> {
+        let external_id = request.external_operation_id.as_deref();
+        if let Some(op) = self.existing_operation(order_id, &OperationType::Refund, external_id)? {
+            return to_value(serde_json::json!({ "operation": op }));
+        }
+        // Как и в API, `orderAmount` учитывает только завершённые возвраты
+        let pending: Money = self
+            .pending_refunds
+            .values()
+            .filter(|(id, _)| id == order_id)
+            .map(|(_, pending)| pending.refund_amount)
+            .sum();
+        let order = &self.order_mut(order_id)?.order;
+        if !matches!(
+            order.payment_status,
+            Some(PaymentStatus::Captured | PaymentStatus::PartiallyRefunded)
+        ) {
+            return Err(invalid_status(&order.payment_status));
+        }
+        let remaining = order.order_amount - pending;
+        if request.refund_amount <= Money::ZERO || request.refund_amount > remaining {
+            return Err(api_error(400, "AMOUNT_MISMATCH", "Invalid refund amount"));
+        }
+        let overdrawn = request
+            .refund_cart
+            .iter()
+            .flat_map(|cart| cart.items.iter())
+            .any(|refunded| {
+                let count = refunded.quantity_count.unwrap_or_default();
+                order.cart.items.iter().any(|item| {
+                    item.product_id == refunded.product_id && item.quantity.count < count
+                })
+            });
+        if overdrawn {
+            return Err(api_error(400, "BAD_REQUEST", "Invalid refund quantity"));
+        }
+        let response = self.push_operation(
+            order_id,
+            OperationType::Refund,
+            request.refund_amount,
+            OperationStatus::Pending,
+            request.external_operation_id.clone(),
+        )?;
+        let operation_id = self.orders[order_id]
+            .operations
+            .last()
+            .unwrap()
+            .operation_id
+            .clone();
+        self.pending_refunds
+            .insert(operation_id, (order_id.to_string(), request));
+        Ok(response)
+    }
+
+    fn rollback(&mut self, order_id: &str) -> R<serde_json::Value> {
+        let order = &mut self.order_mut(order_id)?.order;
+        order.payment_status = Some(PaymentStatus::Failed);
+        order.updated = Some(chrono::Utc::now());
+        Ok(serde_json::json!({}))
+    }
+
+    fn submit(&mut self, order_id: &str, request: SubmitRequest) -> R<serde_json::Value> {
+        let external_id = Some(request.external_operation_id.as_str());
+        if let Some(op) = self.existing_operation(order_id, &OperationType::Submit, external_id)? {
+            return to_value(serde_json::json!({ "operation": op }));
+        }
+        let order = &mut self.order_mut(order_id)?.order;
+        if order.payment_status != Some(PaymentStatus::Confirmed) {
+            return Err(invalid_status(&order.payment_status));
+        }
+        if let Some(cart) = &request.cart {
+            order.cart = cart_from_final(cart, order.cart.cart_id.clone());
+        }
+        if let Some(amount) = request.order_amount {
+            order.order_amount = amount;
+        }
+        order.payment_status = Some(PaymentStatus::Captured);
+        let amount = order.order_amount;
+        self.push_operation(
+            order_id,
+            OperationType::Submit,
+            amount,
+            OperationStatus::Success,
+            Some(request.external_operation_id),
+        )
+    }
+
+    fn get_operation(&mut self, external_operation_id: &str) -> R<serde_json::Value> {
+        let operation = self
+            .orders
+            .values()
+            .flat_map(|order| order.operations.iter())
+            .find(|op| op.external_operation_id.as_deref() == Some(external_operation_id))
+            .ok_or_else(|| api_error(404, "OPERATION_NOT_FOUND", "Operation not found"))?;
+        to_value(serde_json::json!({ "operation": operation }))
+    }
+
+    fn create_subscription(&mut self, request: CreateSubscriptionRequest) -> R<serde_json::Value> {
+        if self.orders.contains_key(&request.order_id) {
+            return Err(api_error(
+                409,
+                "ORDER_ALREADY_EXISTS",
+                "Order already exists",
+            ));
+        }
+        let amount = request.future_write_off_amount.unwrap_or_default();
+        let cart = request.cart.clone().unwrap_or_else(|| {
+            RenderedCart::new()
+                .items(vec![])
+                .total(CartTotal::new().amount(amount).build())
+                .external_id(request.order_id.clone())
+                .build()
+        });
+        self.insert_order(request.order_id.clone(), &cart, request.metadata);
+        let subscription_id = new_id();
+        self.subscriptions.insert(
+            subscription_id.clone(),
+            FakeSubscription {
+                subscription: CustomerSubscriptionResponseData {
+                    status: SubscriptionStatus::New,
+                    subscription_plan_id: new_id(),
+                    cancelled_at: None,
+                    customer_subscription_id: subscription_id.clone(),
+                    is_card_active: true,
+                    next_write_off: None,
+                },
+                order_id: request.order_id.clone(),
+                charge_order_ids: vec![],
+            },
+        );
+        let order = &self.orders[&request.order_id].order;
+        to_value(serde_json::json!({
+            "paymentUrl": order.payment_url,
+            "subscriptionId": subscription_id,
+        }))
+    }
+
+    fn recur(&mut self, request: CreateRecurrentChargeRequest) -> R<serde_json::Value> {
+        let subscription = self
+            .subscriptions
+            .values_mut()
+            .find(|s| s.order_id == request.parent_order_id)
+            .ok_or_else(|| api_error(404, "SUBSCRIPTION_NOT_FOUND", "Subscription not found"))?;
+        if subscription.subscription.status != SubscriptionStatus::Active {
+            return Err(api_error(
+                409,
+                "SUBSCRIPTION_NOT_ACTIVE",
+                "Subscription is not active",
+            ));
+        }
+        if self.orders.contains_key(&request.order_id) {
+            return Err(api_error(
+                409,
+                "ORDER_ALREADY_EXISTS",
+                "Order already exists",
+            ));
+        }
+        subscription.charge_order_ids.push(request.order_id.clone());
+        self.insert_order(request.order_id.clone(), &request.cart, request.metadata);
+        let order = self.order_mut(&request.order_id)?;
+        order.order.order_amount = request.amount;
+        order.order.payment_status = Some(PaymentStatus::Captured);
+        let response = self.push_operation(
+            &request.order_id,
+            OperationType::Recurring,
+            request.amount,
+            OperationStatus::Success,
+            None,
+        )?;
+        to_value(serde_json::json!({ "operationId": response["operation"]["operationId"] }))
+    }
+
+    fn get_subscription(&mut self, subscription_id: &str) -> R<serde_json::Value> {
+        let subscription = self
+            .subscriptions
+            .get(subscription_id)
+            .ok_or_else(|| api_error(404, "SUBSCRIPTION_NOT_FOUND", "Subscription not found"))?;
+        to_value(&subscription.subscription)
+    }
+
+    fn cancel_subscription(&mut self, subscription_id: &str) -> R<serde_json::Value> {
+        let subscription = self
+            .subscriptions
+            .get_mut(subscription_id)
+            .ok_or_else(|| api_error(404, "SUBSCRIPTION_NOT_FOUND", "Subscription not found"))?;
+        let subscription = &mut subscription.subscription;
+        if subscription.status != SubscriptionStatus::Cancelled {
+            subscription.status = SubscriptionStatus::Cancelled;
+            subscription.cancelled_at = Some(chrono::Utc::now());
+            subscription.next_write_off = None;
+        }
+        to_value(&*subscription)
+    }
+
+    fn subscription_charges(
+        &mut self,
+        subscription_id: &str,
+        query: &[(String, String)],
+    ) -> R<serde_json::Value> {
+        let subscription = self
+            .subscriptions
+            .get(subscription_id)
+            .ok_or_else(|| api_error(404, "SUBSCRIPTION_NOT_FOUND", "Subscription not found"))?;
+        let param = |name: &str| -> R<Option<usize>> {
+            query
+                .iter()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.parse())
+                .transpose()
+                .map_err(|_| api_error(400, "BAD_REQUEST", &format!("Invalid {name}")))
+        };
+        let offset = param("offset")?.unwrap_or(0);
+        let limit = param("limit")?.unwrap_or(usize::MAX);
+        let charges = subscription
+            .charge_order_ids
+            .iter()
+            .skip(offset)
+            .take(limit)
+            .filter_map(|order_id| self.orders.get(order_id))
+            .map(|order| SubscriptionCharge {
+                order_id: order.order.order_id.clone(),
+                amount: order.order.order_amount,
+                payment_status: order.order.payment_status.clone(),
+                operation_id: order
+                    .operations
+                    .iter()
+                    .find(|op| op.operation_type == OperationType::Recurring)
+                    .map(|op| op.operation_id.clone()),
+                created: order.order.created,
+            })
+            .collect();
+        to_value(SubscriptionChargesResponseData {
+            customer_subscription_id: subscription_id.to_string(),
+            parent_order_id: subscription.order_id.clone(),
+            status: subscription.subscription.status.clone(),
+            cancelled_at: subscription.subscription.cancelled_at,
+            charges,
+        })
+    }
+}
+
+/// Применяет успешный возврат к корзине, сумме и статусу оплаты заказа
+fn apply_refund(order: &mut BaseMerchantApiOrder, request: &RefundRequest) {
+    for item in &mut order.cart.items {
+        let target = request
+            .target_cart
+            .iter()
+            .flat_map(|cart| cart.items.iter());
+        let refunded = request
+            .refund_cart
+            .iter()
+            .flat_map(|cart| cart.items.iter());
+        if let Some(target) = target.clone().find(|t| t.product_id == item.product_id) {
+            if let Some(count) = target.quantity_count {
+                item.quantity.count = count;
+            }
+            if let Some(price) = target.price {
+                item.final_price = Some(price);
+            }
+        }
+        if let Some(refunded) = refunded.clone().find(|t| t.product_id == item.product_id) {
+            if let Some(count) = refunded.quantity_count {
+                item.quantity.count -= count;
+            }
+            if let Some(price) = refunded.price {
+                item.final_price = unit_price(item).map(|unit| unit - price);
+            }
+        }
+        if let Some(price) = unit_price(item) {
+            item.total = Some(price.mul_quantity(item.quantity.count));
+        }
+    }
+    order.order_amount -= request.refund_amount;
+    order.cart.total.amount = order.order_amount;
+    order.payment_status = Some(if order.cart.total.amount.is_zero() {
+        PaymentStatus::Refunded
+    } else {
+        PaymentStatus::PartiallyRefunded
+    });
+    order.updated = Some(chrono::Utc::now());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn api() -> YandexPayApi<FakeYandexPay> {
+        YandexPayApi::new(
+            "https://sandbox.pay.yandex.ru".into(),
+            "key".into(),
+            FakeYandexPay::new(),
+        )
+    }
+
+    fn cart(amount: i64) -> RenderedCart {
+        RenderedCart::new()
+            .items(vec![
+                RenderedCartItem::new()
+                    .product_id("p1")
+                    .quantity(ItemQuantity::new().count(2.0).available(5.0).build())
+                    .title("Товар")
+                    .total(Money::from_rubles(amount))
+                    .unit_price(Some(Money::from_rubles(amount / 2)))
+                    .build(),
+            ])
+            .total(CartTotal::new().amount(Money::from_rubles(amount)).build())
+            .external_id("cart")
+            .build()
+    }
+
+    async fn create(api: &YandexPayApi<FakeYandexPay>, order_id: &str) {
+        let request = CreateOrderRequest::new()
+            .cart(cart(100))
+            .order_id(order_id)
+            .build();
+        let response = api.create_order(request).await.unwrap();
+        assert!(response.payment_url.starts_with("https://"));
+    }
+
+    fn payment_status(api: &YandexPayApi<FakeYandexPay>, order_id: &str) -> Option<PaymentStatus> {
+        api.client.order(order_id).unwrap().order.payment_status
+    }
+
+    #[tokio::test]
+    async fn test_order_lifecycle() {
+        let api = api();
+        create(&api, "o1").await;
+        let order = api.get_order("o1").await.unwrap();
+        assert_eq!(
+            order.order.unwrap().payment_status,
+            Some(PaymentStatus::Pending)
+        );
+
+        let capture = CaptureOrderRequest::new().build();
+        let err = api.capture_order("o1", capture.clone()).await.unwrap_err();
+        assert_eq!(err.reason_code(), Some(&ReasonCode::OrderHasInvalidStatus));
+        assert!(err.is_conflict());
+
+        api.client
+            .set_payment_status("o1", PaymentStatus::Authorized);
+        let response = api.capture_order("o1", capture).await.unwrap();
+        assert_eq!(response.operation.operation_type, OperationType::Capture);
+        assert_eq!(payment_status(&api, "o1"), Some(PaymentStatus::Captured));
+
+        let refund = RefundRequest::new()
+            .refund_amount(Money::from_rubles(50))
+            .external_operation_id(Some("r1".into()))
+            .build();
+        let response = api.refund_order("o1", refund.clone()).await.unwrap();
+        assert_eq!(response.operation.status, OperationStatus::Pending);
+        // Заказ меняется только после успеха операции
+        assert_eq!(payment_status(&api, "o1"), Some(PaymentStatus::Captured));
+        let order = api.client.order("o1").unwrap().order;
+        assert_eq!(order.order_amount, Money::from_rubles(100));
+        let repeated = api.refund_order("o1", refund).await.unwrap();
+        assert_eq!(
+            repeated.operation.operation_id,
+            response.operation.operation_id
+        );
+        let over = RefundRequest::new()
+            .refund_amount(Money::from_rubles(60))
+            .build();
+        let err = api.refund_order("o1", over).await.unwrap_err();
+        assert_eq!(err.http_status(), Some(400));
+
+        api.client
+            .set_operation_status("r1", OperationStatus::Success);
+        let operation = api.get_operation("r1").await.unwrap();
+        assert_eq!(operation.operation.status, OperationStatus::Success);
+        assert_eq!(
+            payment_status(&api, "o1"),
+            Some(PaymentStatus::PartiallyRefunded)
+        );
+        let order = api.client.order("o1").unwrap().order;
+        assert_eq!(order.order_amount, Money::from_rubles(50));
+
+        let refund = RefundRequest::new()
+            .refund_amount(Money::from_rubles(50))
+            .build();
+        let response = api.refund_order("o1", refund).await.unwrap();
+        api.client
+            .set_operation_status(&response.operation.operation_id, OperationStatus::Fail);
+        assert_eq!(
+            payment_status(&api, "o1"),
+            Some(PaymentStatus::PartiallyRefunded)
+        );
+        let refund = RefundRequest::new()
+            .refund_amount(Money::from_rubles(50))
+            .build();
+        let response = api.refund_order("o1", refund).await.unwrap();
+        api.client
+            .set_operation_status(&response.operation.operation_id, OperationStatus::Success);
+        assert_eq!(payment_status(&api, "o1"), Some(PaymentStatus::Refunded));
+        let order = api.get_order("o1").await.unwrap();
+        assert_eq!(order.operations.len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_refund_more_units_than_bought() {
+        let api = api();
+        create(&api, "o1").await;
+        api.client.set_payment_status("o1", PaymentStatus::Captured);
+        let refund = RefundRequest::new()
+            .refund_amount(Money::from_rubles(50))
+            .refund_cart(Some(
+                TargetCart::new()
+                    .items(vec![
+                        TargetCartItem::new()
+                            .product_id("p1")
+                            .quantity_count(Some(3.0))
+                            .build(),
+                    ])
+                    .build(),
+            ))
+            .build();
+        let err = api.refund_order("o1", refund).await.unwrap_err();
+        assert_eq!(err.http_status(), Some(400));
+        let order = api.client.order("o1").unwrap().order;
+        assert_eq!(order.cart.items[0].quantity.count, 2.0);
+        assert_eq!(order.payment_status, Some(PaymentStatus::Captured));
+    }
+
+    #[tokio::test]
+    async fn test_cancel_rollback_and_submit() {
+        let api = api();
+        create(&api, "o1").await;
+        api.client
+            .set_payment_status("o1", PaymentStatus::Authorized);
+        let cancel = CancelOrderRequest::new().reason("out of stock").build();
+        api.cancel_order("o1", cancel).await.unwrap();
+        assert_eq!(payment_status(&api, "o1"), Some(PaymentStatus::Voided));
+
+        create(&api, "o2").await;
+        api.rollback_order("o2").await.unwrap();
+        assert_eq!(payment_status(&api, "o2"), Some(PaymentStatus::Failed));
+
+        create(&api, "o3").await;
+        api.client
+            .set_payment_status("o3", PaymentStatus::Confirmed);
+        let submit = SubmitRequest::new().external_operation_id("s1").build();
+        api.submit_order("o3", submit).await.unwrap();
+        assert_eq!(payment_status(&api, "o3"), Some(PaymentStatus::Captured));
+
+        let err = api.get_order("missing").await.unwrap_err();
+        assert_eq!(err.reason_code(), Some(&ReasonCode::OrderNotFound));
+        assert_eq!(err.http_status(), Some(404));
+    }
+
+    #[tokio::test]
+    async fn test_subscription_lifecycle() {
+        let api = api();
+        let request = CreateSubscriptionRequest::new()
+            .order_id("start")
+            .future_write_off_amount(Some(Money::from_rubles(100)))
+            .build();
+        let created = api.create_subscription(request).await.unwrap();
+        let subscription = api
+            .get_subscription(
+                &created.subscription_id,
+                GetSubscriptionRequest::new()
+                    .check_card_active(true)
+                    .build(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(subscription.status, SubscriptionStatus::New);
+
+        let recur = CreateRecurrentChargeRequest::new()
+            .amount(Money::from_rubles(100))
+            .cart(cart(100))
+            .order_id("charge-1")
+            .parent_order_id("start")
+            .build();
+        assert!(api.recur_subscription(recur.clone()).await.is_err());
+
+        api.client
+            .set_payment_status("start", PaymentStatus::Captured);
+        api.recur_subscription(recur).await.unwrap();
+        assert_eq!(
+            payment_status(&api, "charge-1"),
+            Some(PaymentStatus::Captured)
+        );
+
+        let recur = CreateRecurrentChargeRequest::new()
+            .amount(Money::from_rubles(50))
+            .cart(cart(50))
+            .order_id("charge-2")
+            .parent_order_id("start")
+            .build();
+        api.recur_subscription(recur).await.unwrap();
+        let history = api
+            .list_subscription_charges(
+                &created.subscription_id,
+                ListSubscriptionChargesRequest::new().build(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(history.parent_order_id, "start");
+        assert_eq!(history.status, SubscriptionStatus::Active);
+        assert!(history.cancelled_at.is_none());
+        let charges: Vec<_> = history
+            .charges
+            .iter()
+            .map(|charge| (charge.order_id.as_str(), charge.amount))
+            .collect();
+        assert_eq!(
+            charges,
+            vec![
+                ("charge-1", Money::from_rubles(100)),
+                ("charge-2", Money::from_rubles(50))
+            ]
+        );
+        assert!(history.charges[0].operation_id.is_some());
+        let page = api
+            .list_subscription_charges(
+                &created.subscription_id,
+                ListSubscriptionChargesRequest::new()
+                    .limit(Some(1))
+                    .offset(Some(1))
+                    .build(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(page.charges.len(), 1);
+        assert_eq!(page.charges[0].order_id, "charge-2");
+
+        let cancelled = api
+            .cancel_subscription(&created.subscription_id)
+            .await
+            .unwrap();
+        assert_eq!(cancelled.status, SubscriptionStatus::Cancelled);
+        let cancelled_at = cancelled.cancelled_at.unwrap();
+        let again = api
+            .cancel_subscription(&created.subscription_id)
+            .await
+            .unwrap();
+        assert_eq!(again.cancelled_at, Some(cancelled_at));
+        let recur = CreateRecurrentChargeRequest::new()
+            .amount(Money::from_rubles(100))
+            .cart(cart(100))
+            .order_id("charge-3")
+            .parent_order_id("start")
+            .build();
+        let err = api.recur_subscription(recur).await.unwrap_err();
+        assert_eq!(
+            err.reason_code(),
+            Some(&ReasonCode::Unknown("SUBSCRIPTION_NOT_ACTIVE".into()))
+        );
+        let history = api
+            .list_subscription_charges(
+                &created.subscription_id,
+                ListSubscriptionChargesRequest::new().build(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(history.status, SubscriptionStatus::Cancelled);
+        assert_eq!(history.cancelled_at, Some(cancelled_at));
+        assert_eq!(history.charges.len(), 2);
+    }
+}
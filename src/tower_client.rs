@@ -0,0 +1,201 @@
+//! Адаптер, превращающий любой `tower::Service` в [`HttpClient`].
+
+use std::time::Duration;
+
+use bytes::Bytes;
+use tower_service::Service;
+
+use crate::http_request::read_http_response;
+use crate::*;
+
+/// [`HttpClient`] поверх `tower::Service<http::Request<Bytes>>`.
+///
+/// Позволяет поставить перед транспортом стандартные слои tower: метрики, трассировку,
+/// ограничение частоты, circuit breaker. Сервис клонируется на каждый запрос, поэтому
+/// общее состояние слоёв должно храниться за `Arc`, как это принято в tower.
+///
+/// ```no_run
+/// # use yandex_pay_api::*;
+/// # use bytes::Bytes;
+/// # fn run<S>(transport: S)
+/// # where
+/// #     S: tower::Service<http::Request<Bytes>, Response = http::Response<String>> + Clone,
+/// #     S::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+/// # {
+/// let service = tower::ServiceBuilder::new()
+///     .map_request(|request: http::Request<Bytes>| {
+///         tracing::info!("{} {}", request.method(), request.uri());
+///         request
+///     })
+///     .service(transport);
+/// let api = YandexPayApi::new(
+///     "https://sandbox.pay.yandex.ru".into(),
+///     "merchant-id".into(),
+///     TowerClient::new(service),
+/// );
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct TowerClient<S> {
+    pub service: S,
+}
+
+impl<S> TowerClient<S> {
+    pub fn new(service: S) -> Self {
+        TowerClient { service }
+    }
+}
+
+impl<S, B> TowerClient<S>
+where
+    S: Service<http::Request<Bytes>, Response = http::Response<B>> + Clone,
+    S::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+    B: http_body::Body,
+    B::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    async fn call(&self, request: YandexPayApiRequest) -> R<RawResponse> {
+        let mut service = self.service.clone();
+        let timeout = Duration::from_millis(request.request_timeout.into());
+        let request = request.into_http_request()?;
+        let send = async {
+            futures_util::future::poll_fn(|cx| service.poll_ready(cx))
+                .await
+                .map_err(YandexPayApiError::transport)?;
+            let response = service
+                .call(request)
+                .await
+                .map_err(YandexPayApiError::transport)?;
+            read_http_response(response).await
+        };
+        tokio::time::timeout(timeout, send)
+            .await
+            .map_err(|_| YandexPayApiError::Timeout)?
+    }
+}
+
+impl<S, B> HttpClient for TowerClient<S>
+where
+    S: Service<http::Request<Bytes>, Response = http::Response<B>> + Clone,
+    S::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+    B: http_body::Body,
+    B::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    async fn send<T: serde::de::DeserializeOwned>(&self, request: YandexPayApiRequest) -> R<T> {
+        self.call(request).await?.decode()
+    }
+}
+
+/// Для `dyn RawHttpClient` сервис и его ответы должны быть `Send`
+impl<S, B> RawHttpClient for TowerClient<S>
+where
+    S: Service<http::Request<Bytes>, Response = http::Response<B>> + Clone + Send + Sync + 'static,
+    S::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+    S::Future: Send,
+    B: http_body::Body + Send + 'static,
+    B::Data: Send,
+    B::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    fn send_raw(&self, request: YandexPayApiRequest) -> RawFuture<'_> {
+        Box::pin(self.call(request))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+
+    #[test]
+    fn test_into_http_request() {
+        let request = YandexPayApiRequest::new()
+            .url("https://sandbox.pay.yandex.ru/api/merchant/v1/orders")
+            .api_key("key")
+            .request_id("request-1")
+            .method(Method::Post)
+            .body(Some(Bytes::from_static(b"{}")))
+            .build();
+        let request = http::Request::<Bytes>::try_from(request).unwrap();
+        assert_eq!(request.method(), http::Method::POST);
+        assert_eq!(
+            request.uri(),
+            "https://sandbox.pay.yandex.ru/api/merchant/v1/orders"
+        );
+        assert_eq!(request.headers()["authorization"], "Api-Key key");
+        assert_eq!(request.headers()["x-request-id"], "request-1");
+        assert_eq!(request.headers()["x-request-attempt"], "0");
+        assert_eq!(request.headers()["content-type"], "application/json");
+        assert_eq!(request.body(), &Bytes::from_static(b"{}"));
+
+        let request = YandexPayApiRequest::new()
+            .url("https://sandbox.pay.yandex.ru/api/merchant/v1/subscriptions/sub-1")
+            .api_key("key")
+            .query(vec![("checkCardActive".into(), "true".into())])
+            .build();
+        let request = request.into_http_request().unwrap();
+        assert_eq!(
+            request.uri(),
+            "https://sandbox.pay.yandex.ru/api/merchant/v1/subscriptions/sub-1?checkCardActive=true"
+        );
+        assert!(request.body().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_tower_layers_compose_in_front_of_transport() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let transport = {
+            let seen = seen.clone();
+            tower::service_fn(move |request: http::Request<Bytes>| {
+                seen.lock().unwrap().push((
+                    request.uri().path().to_string(),
+                    request.headers().get("x-trace-id").cloned(),
+                ));
+                async move {
+                    let body = r#"{"status": "success", "data": {"operation": {"amount": "10.00", "operationId": "op", "operationType": "REFUND", "orderId": "order-1", "status": "SUCCESS"}}}"#;
+                    Ok::<_, Infallible>(http::Response::new(body.to_string()))
+                }
+            })
+        };
+        let service = tower::ServiceBuilder::new()
+            .map_request(|mut request: http::Request<Bytes>| {
+                request
+                    .headers_mut()
+                    .insert("x-trace-id", http::HeaderValue::from_static("trace-1"));
+                request
+            })
+            .service(transport);
+        let api = YandexPayApi::new(
+            "http://localhost".into(),
+            "key".into(),
+            TowerClient::new(service),
+        );
+
+        let response = api.get_operation("ext-1").await.unwrap();
+        assert_eq!(response.operation.order_id, "order-1");
+        assert_eq!(
+            *seen.lock().unwrap(),
+            vec![(
+                "/api/merchant/v1/operations/ext-1".to_string(),
+                Some(http::HeaderValue::from_static("trace-1"))
+            )]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_tower_service_error_is_transport() {
+        let service = tower::service_fn(|_: http::Request<Bytes>| async {
+            Err::<http::Response<String>, _>(std::io::Error::other("connection reset"))
+        });
+        let api = YandexPayApi::new(
+            "http://localhost".into(),
+            "key".into(),
+            TowerClient::new(service),
+        );
+        let result = api.get_order("order").await;
+        assert!(
+            matches!(result, Err(YandexPayApiError::Transport(_))),
+            "{result:?}"
+        );
+    }
+}
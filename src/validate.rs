@@ -0,0 +1,388 @@
+use std::collections::HashSet;
+use std::fmt;
+
+use crate::*;
+
+/// Максимальная длина строковых полей, ограниченных в документации Yandex Pay
+pub const MAX_STRING_LENGTH: usize = 2048;
+/// Допустимое время жизни ссылки на оплату (сек)
+pub const TTL_RANGE: std::ops::RangeInclusive<u32> = 180..=604800;
+
+/// Нарушение ограничения API в конкретном поле запроса
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    /// Путь к полю, например `cart.items[0].title`
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+/// Все нарушения, найденные при проверке запроса
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub struct ValidationError {
+    pub violations: Vec<Violation>,
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid request: ")?;
+        for (i, violation) in self.violations.iter().enumerate() {
+            if i > 0 {
+                write!(f, "; ")?;
+            }
+            write!(f, "{}", violation)?;
+        }
+        Ok(())
+    }
+}
+
+/// Накопитель нарушений с текущим путём к полю
+#[derive(Debug, Default)]
+pub struct Violations {
+    path: String,
+    violations: Vec<Violation>,
+}
+
+impl Violations {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Добавляет нарушение для поля `field` относительно текущего пути
+    pub fn push(&mut self, field: &str, message: impl Into<String>) {
+        self.violations.push(Violation {
+            path: self.join(field),
+            message: message.into(),
+        });
+    }
+
+    /// Проверяет вложенное значение, добавляя `field` к пути
+    pub fn nested<V: Validate + ?Sized>(&mut self, field: &str, value: &V) {
+        let path = self.join(field);
+        let parent = std::mem::replace(&mut self.path, path);
+        value.validate_into(self);
+        self.path = parent;
+    }
+
+    pub fn into_result(self) -> Result<(), ValidationError> {
+        if self.violations.is_empty() {
+            Ok(())
+        } else {
+            Err(ValidationError {
+                violations: self.violations,
+            })
+        }
+    }
+
+    fn join(&self, field: &str) -> String {
+        if self.path.is_empty() || field.starts_with('[') {
+            format!("{}{}", self.path, field)
+        } else {
+            format!("{}.{}", self.path, field)
+        }
+    }
+
+    fn max_length(&mut self, field: &str, value: Option<&str>) {
+        if let Some(value) = value {
+            let length = value.chars().count();
+            if length > MAX_STRING_LENGTH {
+                self.push(
+                    field,
+                    format!("length {length} exceeds {MAX_STRING_LENGTH} characters"),
+                );
+            }
+        }
+    }
+
+    fn ttl(&mut self, ttl: Option<u32>) {
+        if let Some(ttl) = ttl
+            && !TTL_RANGE.contains(&ttl)
+        {
+            self.push(
+                "ttl",
+                format!(
+                    "{ttl} is out of range {}..={}",
+                    TTL_RANGE.start(),
+                    TTL_RANGE.end()
+                ),
+            );
+        }
+    }
+
+    fn non_negative(&mut self, field: &str, value: Option<Money>) {
+        if let Some(value) = value
+            && value.is_negative()
+        {
+            self.push(field, format!("amount {value} is negative"));
+        }
+    }
+}
+
+/// Локальная проверка ограничений API перед отправкой запроса
+pub trait Validate {
+    /// Добавляет найденные нарушения в `violations`
+    fn validate_into(&self, violations: &mut Violations);
+
+    /// Возвращает все найденные нарушения с путями к полям
+    fn validate(&self) -> Result<(), ValidationError> {
+        let mut violations = Violations::new();
+        self.validate_into(&mut violations);
+        violations.into_result()
+    }
+}
+
+impl<V: Validate> Validate for [V] {
+    fn validate_into(&self, violations: &mut Violations) {
+        for (i, value) in self.iter().enumerate() {
+            violations.nested(&format!("[{i}]"), value);
+        }
+    }
+}
+
+impl Validate for CreateOrderRequest {
+    fn validate_into(&self, violations: &mut Violations) {
+        violations.nested("cart", &self.cart);
+        violations.max_length("metadata", self.metadata.as_deref());
+        violations.ttl(self.ttl);
+        if let Some(extensions) = &self.extensions {
+            violations.nested("extensions", extensions);
+        }
+        if let Some(risk) = &self.risk {
+            violations.max_length("risk.deviceId", risk.device_id.as_deref());
+        }
+        if let Some(uniqr_metadata) = &self.uniqr_metadata {
+            violations.max_length(
+                "uniqrMetadata.uniQrId",
+                Some(uniqr_metadata.uni_qr_id.as_str()),
+            );
+        }
+    }
+}
+
+impl Validate for OrderExtensions {
+    fn validate_into(&self, violations: &mut Violations) {
+        if let Some(billing_report) = &self.billing_report {
+            violations.max_length(
+                "billingReport.branchId",
+                billing_report.branch_id.as_deref(),
+            );
+            violations.max_length(
+                "billingReport.managerId",
+                billing_report.manager_id.as_deref(),
+            );
+        }
+        if let Some(qr_data) = &self.qr_data {
+            violations.max_length("qrData.token", Some(qr_data.token.as_str()));
+        }
+        if let Some(sms_offer) = &self.sms_offer {
+            violations.nested("smsOffer", sms_offer);
+        }
+    }
+}
+
+impl Validate for SMSOffer {
+    fn validate_into(&self, violations: &mut Violations) {
+        violations.max_length("phone", Some(self.phone.as_str()));
+        let is_valid = self
+            .phone
+            .strip_prefix('+')
+            .is_some_and(|digits| !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()));
+        if !is_valid {
+            violations.push("phone", "must match ^\\+\\d+$");
+        }
+    }
+}
+
+impl Validate for RenderedCart {
+    fn validate_into(&self, violations: &mut Violations) {
+        violations.nested("items", self.items.as_slice());
+        let mut product_ids = HashSet::new();
+        for (i, item) in self.items.iter().enumerate() {
+            if !product_ids.insert(item.product_id.as_str()) {
+                violations.push(
+                    &format!("items[{i}].productId"),
+                    format!("duplicate productId {:?}", item.product_id),
+                );
+            }
+        }
+        violations.non_negative("total.amount", Some(self.total.amount));
+    }
+}
+
+impl Validate for RenderedCartItem {
+    fn validate_into(&self, violations: &mut Violations) {
+        if self.product_id.is_empty() {
+            violations.push("productId", "must not be empty");
+        }
+        if self.quantity.count <= 0.0 {
+            violations.push("quantity.count", "must be positive");
+        }
+        violations.max_length("title", Some(self.title.as_str()));
+        violations.max_length("description", self.description.as_deref());
+        violations.non_negative("total", Some(self.total));
+        violations.non_negative("discountedUnitPrice", self.discounted_unit_price);
+        violations.non_negative("subtotal", self.subtotal);
+        violations.non_negative("unitPrice", self.unit_price);
+        if let Some(receipt) = &self.receipt {
+            violations.nested("receipt", receipt);
+        }
+    }
+}
+
+impl Validate for ItemReceipt {
+    fn validate_into(&self, violations: &mut Violations) {
+        violations.max_length("title", self.title.as_deref());
+        violations.non_negative("excise", self.excise);
+        if let Some(agent) = &self.agent {
+            violations.max_length("agent.operation", agent.operation.as_deref());
+            for (i, phone) in agent.phones.iter().enumerate() {
+                violations.max_length(&format!("agent.phones[{i}]"), Some(phone.as_str()));
+            }
+        }
+        if let Some(supplier) = &self.supplier {
+            violations.max_length("supplier.inn", Some(supplier.inn.as_str()));
+            violations.max_length("supplier.name", Some(supplier.name.as_str()));
+            for (i, phone) in supplier.phones.iter().enumerate() {
+                violations.max_length(&format!("supplier.phones[{i}]"), Some(phone.as_str()));
+            }
+        }
+    }
+}
+
+impl Validate for CreateSubscriptionRequest {
+    fn validate_into(&self, violations: &mut Violations) {
+        if let Some(cart) = &self.cart {
+            violations.nested("cart", cart);
+        }
+        violations.max_length("metadata", self.metadata.as_deref());
+        violations.max_length("title", self.title.as_deref());
+        violations.ttl(self.ttl);
+        violations.non_negative("futureWriteOffAmount", self.future_write_off_amount);
+        if let Some(interval_count) = self.interval_count
+            && interval_count <= 0
+        {
+            violations.push("intervalCount", "must be positive");
+        }
+        if let Some(trial_count) = self.trial_count
+            && trial_count < 0
+        {
+            violations.push("trialCount", "must not be negative");
+        }
+    }
+}
+
+impl Validate for CreateRecurrentChargeRequest {
+    fn validate_into(&self, violations: &mut Violations) {
+        violations.nested("cart", &self.cart);
+        violations.max_length("metadata", self.metadata.as_deref());
+        if self.amount <= Money::ZERO {
+            violations.push("amount", "must be positive");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_client::NoClient;
+
+    fn item(product_id: &str) -> RenderedCartItem {
+        RenderedCartItem::new()
+            .product_id(product_id)
+            .quantity(ItemQuantity::new().count(1.0).available(1.0).build())
+            .title("Товар")
+            .total(Money::from_rubles(10))
+            .build()
+    }
+
+    fn order(items: Vec<RenderedCartItem>) -> CreateOrderRequest {
+        CreateOrderRequest::new()
+            .cart(
+                RenderedCart::new()
+                    .items(items)
+                    .total(CartTotal::new().amount(Money::from_rubles(10)).build())
+                    .external_id("cart")
+                    .build(),
+            )
+            .order_id("order")
+            .build()
+    }
+
+    fn paths(error: ValidationError) -> Vec<String> {
+        error.violations.into_iter().map(|v| v.path).collect()
+    }
+
+    #[test]
+    fn test_valid_order() {
+        assert_eq!(order(vec![item("a"), item("b")]).validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_collects_all_violations() {
+        let mut long = item("b");
+        long.title = "x".repeat(MAX_STRING_LENGTH + 1);
+        long.receipt = Some(
+            ItemReceipt::new()
+                .tax(Tax::Vat20)
+                .title(Some("x".repeat(MAX_STRING_LENGTH + 1)))
+                .build(),
+        );
+        let mut request = order(vec![item("a"), long, item("a")]);
+        request.ttl = Some(60);
+        request.metadata = Some("x".repeat(MAX_STRING_LENGTH + 1));
+        request.extensions = Some(
+            OrderExtensions::new()
+                .sms_offer(Some(SMSOffer::new().phone("8 (912) 000").build()))
+                .build(),
+        );
+        let error = request.validate().unwrap_err();
+        assert_eq!(
+            paths(error),
+            vec![
+                "cart.items[1].title",
+                "cart.items[1].receipt.title",
+                "cart.items[2].productId",
+                "metadata",
+                "ttl",
+                "extensions.smsOffer.phone",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_order_validates_before_sending() {
+        let api = YandexPayApi::new("http://localhost".into(), "key".into(), NoClient)
+            .with_validation(true);
+        let result = api.create_order(order(vec![item("a"), item("a")])).await;
+        let Err(YandexPayApiError::Validation(error)) = result else {
+            panic!("expected validation error, got {result:?}");
+        };
+        assert_eq!(paths(error), vec!["cart.items[1].productId"]);
+    }
+
+    #[test]
+    fn test_subscription_requests() {
+        let request = CreateSubscriptionRequest::new()
+            .order_id("order")
+            .ttl(Some(604801))
+            .interval_count(Some(0))
+            .build();
+        assert_eq!(
+            paths(request.validate().unwrap_err()),
+            vec!["ttl", "intervalCount"]
+        );
+
+        let request = CreateRecurrentChargeRequest::new()
+            .amount(Money::ZERO)
+            .cart(order(vec![item("a")]).cart)
+            .order_id("charge")
+            .parent_order_id("order")
+            .build();
+        assert_eq!(paths(request.validate().unwrap_err()), vec!["amount"]);
+    }
+}
@@ -0,0 +1,307 @@
+use std::time::Duration;
+
+use builder_pattern::Builder;
+use futures_util::Stream;
+use tokio::time::Instant;
+
+use crate::*;
+
+/// Политика опроса статуса операции.
+#[derive(Debug, Clone, Builder)]
+pub struct PollPolicy {
+    #[default(Duration::from_millis(500))]
+    /// Задержка перед вторым запросом
+    pub initial_interval: Duration,
+    #[default(Duration::from_secs(10))]
+    /// Максимальная задержка между запросами
+    pub max_interval: Duration,
+    #[default(1.5)]
+    /// Множитель задержки для каждого следующего запроса
+    pub multiplier: f64,
+    #[default(Duration::from_secs(120))]
+    /// Общее время ожидания, после которого возвращается [`YandexPayApiError::OperationTimeout`]
+    pub timeout: Duration,
+}
+
+impl Default for PollPolicy {
+    fn default() -> Self {
+        PollPolicy::new().build()
+    }
+}
+
+impl PollPolicy {
+    fn next_interval(&self, interval: Duration) -> Duration {
+        interval
+            .mul_f64(self.multiplier.max(1.0))
+            .min(self.max_interval)
+    }
+}
+
+impl OperationStatus {
+    /// Операция завершена (`SUCCESS` или `FAIL`)
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, OperationStatus::Success | OperationStatus::Fail)
+    }
+}
+
+/// Состояние опроса одной операции
+struct Poller<'a, C: HttpClient> {
+    api: &'a YandexPayApi<C>,
+    external_operation_id: String,
+    policy: PollPolicy,
+    deadline: Instant,
+    interval: Duration,
+    polled: bool,
+    last: Option<Operation>,
+    done: bool,
+}
+
+impl<'a, C: HttpClient> Poller<'a, C> {
+    fn new(api: &'a YandexPayApi<C>, external_operation_id: String, policy: PollPolicy) -> Self {
+        Poller {
+            api,
+            external_operation_id,
+            deadline: Instant::now() + policy.timeout,
+            interval: policy.initial_interval,
+            policy,
+            polled: false,
+            last: None,
+            done: false,
+        }
+    }
+
+    /// Опрашивает операцию до смены статуса. `None` — опрос завершён.
+    ///
+    /// Временные ошибки ([`YandexPayApiError::is_retryable`]) не прерывают опрос, а после
+    /// истечения `policy.timeout` превращаются в [`YandexPayApiError::OperationTimeout`].
+    /// Остальные ошибки завершают опрос.
+    async fn next_change(&mut self) -> Option<R<Operation>> {
+        if self.done {
+            return None;
+        }
+        loop {
+            if self.polled {
+                let now = Instant::now();
+                if now >= self.deadline {
+                    self.done = true;
+                    return Some(Err(YandexPayApiError::OperationTimeout {
+                        external_operation_id: self.external_operation_id.clone(),
+                        last: self.last.take().map(Box::new),
+                    }));
+                }
+                tokio::time::sleep(self.interval.min(self.deadline - now)).await;
+                self.interval = self.policy.next_interval(self.interval);
+            }
+            self.polled = true;
+            let operation = match self.api.get_operation(&self.external_operation_id).await {
+                Ok(response) => response.operation,
+                // Истечение срока проверяется в начале цикла
+                Err(err) if err.is_retryable() => {
+                    tracing::warn!("Retrying operation status request: {}", err);
+                    continue;
+                }
+                Err(err) => {
+                    self.done = true;
+                    return Some(Err(err));
+                }
+            };
+            let changed = self
+                .last
+                .as_ref()
+                .is_none_or(|last| last.status != operation.status);
+            self.done = operation.status.is_terminal();
+            self.last = Some(operation.clone());
+            if changed || self.done {
+                return Some(Ok(operation));
+            }
+        }
+    }
+}
+
+impl<C: HttpClient> YandexPayApi<C> {
+    /// Опрашивает `get_operation` с увеличивающейся задержкой, пока операция не перейдёт
+    /// в статус `SUCCESS` или `FAIL`.
+    ///
+    /// Возвращает итоговую операцию или [`YandexPayApiError::OperationTimeout`], если
+    /// `policy.timeout` истёк раньше. Временные ошибки запроса (таймаут, 429, 5xx) не
+    /// прерывают ожидание, остальные ошибки возвращаются сразу.
+    pub async fn wait_for_operation(
+        &self,
+        external_operation_id: impl Into<String>,
+        policy: PollPolicy,
+    ) -> R<Operation> {
+        let mut poller = Poller::new(self, external_operation_id.into(), policy);
+        loop {
+            match poller.next_change().await {
+                Some(Ok(operation)) if operation.status.is_terminal() => return Ok(operation),
+                Some(Ok(_)) => {}
+                Some(Err(err)) => return Err(err),
+                None => unreachable!("poller stops only after a terminal status or an error"),
+            }
+        }
+    }
+
+    /// Как [`YandexPayApi::wait_for_operation`], но возвращает поток с операцией при каждом
+    /// изменении статуса. Поток завершается после терминального статуса или ошибки.
+    pub fn watch_operation(
+        &self,
+        external_operation_id: impl Into<String>,
+        policy: PollPolicy,
+    ) -> impl Stream<Item = R<Operation>> + '_ {
+        let poller = Poller::new(self, external_operation_id.into(), policy);
+        futures_util::stream::unfold(poller, |mut poller| async move {
+            let item = poller.next_change().await?;
+            Some((item, poller))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use futures_util::StreamExt;
+
+    use super::*;
+
+    #[derive(Clone, Default)]
+    struct SequenceClient {
+        statuses: Arc<Mutex<Vec<&'static str>>>,
+        calls: Arc<Mutex<u32>>,
+    }
+
+    impl HttpClient for SequenceClient {
+        async fn send<T: serde::de::DeserializeOwned>(&self, _: YandexPayApiRequest) -> R<T> {
+            *self.calls.lock().unwrap() += 1;
+            let mut statuses = self.statuses.lock().unwrap();
+            let status = if statuses.len() > 1 {
+                statuses.remove(0)
+            } else {
+                statuses[0]
+            };
+            // Числовой статус имитирует ответ API с этим HTTP-кодом
+            if let Ok(code) = status.parse::<u16>() {
+                return decode_response(code, b"{}");
+            }
+            Ok(serde_json::from_value(serde_json::json!({
+                "operation": {
+                    "amount": "10.00",
+                    "operationId": "op",
+                    "operationType": "REFUND",
+                    "orderId": "order",
+                    "externalOperationId": "ext",
+                    "status": status,
+                    "created": null,
+                    "updated": null
+                }
+            }))?)
+        }
+    }
+
+    fn api(statuses: Vec<&'static str>) -> YandexPayApi<SequenceClient> {
+        let client = SequenceClient::default();
+        *client.statuses.lock().unwrap() = statuses;
+        YandexPayApi::new("http://localhost".into(), "key".into(), client)
+    }
+
+    fn policy(timeout: Duration) -> PollPolicy {
+        PollPolicy::new()
+            .initial_interval(Duration::from_millis(1))
+            .max_interval(Duration::from_millis(5))
+            .timeout(timeout)
+            .build()
+    }
+
+    #[tokio::test]
+    async fn test_wait_until_terminal() {
+        let api = api(vec!["PENDING", "PENDING", "PENDING", "SUCCESS"]);
+        let operation = api
+            .wait_for_operation("ext", policy(Duration::from_secs(5)))
+            .await
+            .unwrap();
+        assert_eq!(operation.status, OperationStatus::Success);
+        assert_eq!(*api.client.calls.lock().unwrap(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_wait_retries_transient_errors() {
+        let api = api(vec!["PENDING", "503", "429", "SUCCESS"]);
+        let operation = api
+            .wait_for_operation("ext", policy(Duration::from_secs(5)))
+            .await
+            .unwrap();
+        assert_eq!(operation.status, OperationStatus::Success);
+        assert_eq!(*api.client.calls.lock().unwrap(), 4);
+
+        let api = self::api(vec!["503", "SUCCESS"]);
+        let operation = api
+            .wait_for_operation("ext", policy(Duration::from_secs(5)))
+            .await
+            .unwrap();
+        assert_eq!(operation.status, OperationStatus::Success);
+    }
+
+    #[tokio::test]
+    async fn test_wait_stops_on_permanent_error() {
+        let api = api(vec!["PENDING", "404", "SUCCESS"]);
+        let err = api
+            .wait_for_operation("ext", policy(Duration::from_secs(5)))
+            .await
+            .unwrap_err();
+        assert_eq!(err.http_status(), Some(404));
+        assert_eq!(*api.client.calls.lock().unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_wait_times_out() {
+        let api = api(vec!["PENDING"]);
+        let err = api
+            .wait_for_operation("ext", policy(Duration::from_millis(30)))
+            .await
+            .unwrap_err();
+        let YandexPayApiError::OperationTimeout {
+            external_operation_id,
+            last,
+        } = err
+        else {
+            panic!("expected timeout, got {err:?}");
+        };
+        assert_eq!(external_operation_id, "ext");
+        assert_eq!(last.unwrap().status, OperationStatus::Pending);
+    }
+
+    #[tokio::test]
+    async fn test_wait_times_out_on_transient_errors() {
+        for (statuses, last) in [
+            (vec!["PENDING", "503"], Some(OperationStatus::Pending)),
+            (vec!["503"], None),
+        ] {
+            let api = api(statuses);
+            let err = api
+                .wait_for_operation("ext", policy(Duration::from_millis(30)))
+                .await
+                .unwrap_err();
+            let YandexPayApiError::OperationTimeout {
+                last: timeout_last, ..
+            } = err
+            else {
+                panic!("expected timeout, got {err:?}");
+            };
+            assert_eq!(timeout_last.map(|op| op.status), last);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_watch_yields_status_changes() {
+        let api = api(vec!["PENDING", "PENDING", "FAIL"]);
+        let statuses: Vec<OperationStatus> = api
+            .watch_operation("ext", policy(Duration::from_secs(5)))
+            .map(|operation| operation.unwrap().status)
+            .collect()
+            .await;
+        assert_eq!(
+            statuses,
+            vec![OperationStatus::Pending, OperationStatus::Fail]
+        );
+    }
+}
@@ -0,0 +1,544 @@
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use p256::ecdsa::signature::Verifier;
+use p256::ecdsa::{Signature, VerifyingKey};
+use serde::{Deserialize, Serialize};
+
+use crate::serde_help::*;
+use crate::*;
+
+/// Ошибки проверки уведомления Yandex Pay
+#[derive(Debug, thiserror::Error)]
+pub enum WebhookError {
+    #[error("Malformed webhook token: {0}")]
+    Malformed(S),
+    #[error("Unsupported webhook token algorithm: {0}")]
+    UnsupportedAlgorithm(S),
+    #[error("Unknown webhook signing key: {0:?}")]
+    UnknownKey(Option<S>),
+    #[error("Invalid webhook key: {0}")]
+    InvalidKey(S),
+    #[error("Invalid webhook signature")]
+    InvalidSignature,
+    #[error("Webhook token is missing claim: {0}")]
+    MissingClaim(&'static str),
+    #[error("Webhook token is expired")]
+    Expired,
+    #[error("Webhook token is issued in the future")]
+    IssuedInFuture,
+    #[error("Webhook merchant id mismatch: {0}")]
+    MerchantMismatch(S),
+    #[error("Webhook payload error: {0}")]
+    Serde(#[from] serde_json::Error),
+}
+
+/// Набор публичных ключей Yandex Pay для проверки подписи уведомлений.
+///
+/// Ключи доступны по адресу `{base_url}/api/jwks`.
+/// <https://pay.yandex.ru/docs/ru/custom/backend/merchant-api/webhook>
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Jwks {
+    pub keys: Vec<Jwk>,
+}
+
+/// Публичный ключ в формате JWK
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Jwk {
+    /// Тип ключа, для ES256 — `EC`
+    pub kty: String,
+    /// Идентификатор ключа
+    pub kid: Option<String>,
+    /// Алгоритм подписи
+    pub alg: Option<String>,
+    /// Кривая, для ES256 — `P-256`
+    pub crv: Option<String>,
+    /// Координата X в base64url
+    pub x: Option<String>,
+    /// Координата Y в base64url
+    pub y: Option<String>,
+}
+
+impl Jwk {
+    fn verifying_key(&self) -> R<VerifyingKey, WebhookError> {
+        if self.kty != "EC" || self.crv.as_deref().is_some_and(|crv| crv != "P-256") {
+            return Err(WebhookError::InvalidKey("expected EC P-256 key".into()));
+        }
+        let coordinate = |value: &Option<String>| -> R<[u8; 32], WebhookError> {
+            let value = value
+                .as_deref()
+                .ok_or_else(|| WebhookError::InvalidKey("missing coordinate".into()))?;
+            let bytes = URL_SAFE_NO_PAD
+                .decode(value)
+                .map_err(|err| WebhookError::InvalidKey(err.to_string().into()))?;
+            bytes
+                .try_into()
+                .map_err(|_| WebhookError::InvalidKey("invalid coordinate length".into()))
+        };
+        let x = coordinate(&self.x)?;
+        let y = coordinate(&self.y)?;
+        let point = p256::EncodedPoint::from_affine_coordinates(&x.into(), &y.into(), false);
+        VerifyingKey::from_encoded_point(&point)
+            .map_err(|err| WebhookError::InvalidKey(err.to_string().into()))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct JwtHeader {
+    alg: String,
+    kid: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JwtClaims {
+    iat: Option<i64>,
+    exp: Option<i64>,
+    #[serde(rename = "merchantId")]
+    merchant_id: Option<String>,
+}
+
+/// Проверяет подпись уведомлений Yandex Pay и разбирает их содержимое.
+///
+/// Уведомления приходят в теле запроса `/v1/webhook` в виде JWT, подписанного алгоритмом ES256.
+#[derive(Debug, Clone)]
+pub struct WebhookVerifier {
+    pub jwks: Jwks,
+    pub merchant_id: S,
+    /// Допустимое расхождение часов при проверке `iat`/`exp`
+    pub leeway: chrono::Duration,
+}
+
+impl WebhookVerifier {
+    pub fn new(jwks: Jwks, merchant_id: S) -> Self {
+        WebhookVerifier {
+            jwks,
+            merchant_id,
+            leeway: chrono::Duration::seconds(60),
+        }
+    }
+
+    pub fn with_leeway(mut self, leeway: chrono::Duration) -> Self {
+        self.leeway = leeway;
+        self
+    }
+
+    /// Проверяет подпись, срок действия и продавца уведомления.
+    pub fn verify(&self, token: &str) -> R<WebhookNotification, WebhookError> {
+        self.verify_at(token, chrono::Utc::now())
+    }
+
+    /// То же, что [`WebhookVerifier::verify`], но относительно переданного момента времени.
+    pub fn verify_at(&self, token: &str, now: Time) -> R<WebhookNotification, WebhookError> {
+        self.verify_payload_at(token, now)
+    }
+
+    /// Проверяет JWT так же, как [`WebhookVerifier::verify`], и разбирает его содержимое в `T`.
+    ///
+    /// Используется для других запросов Yandex Pay к продавцу, например `/v1/order/render`.
+    pub fn verify_payload<T: serde::de::DeserializeOwned>(
+        &self,
+        token: &str,
+    ) -> R<T, WebhookError> {
+        self.verify_payload_at(token, chrono::Utc::now())
+    }
+
+    pub fn verify_payload_at<T: serde::de::DeserializeOwned>(
+        &self,
+        token: &str,
+        now: Time,
+    ) -> R<T, WebhookError> {
+        let (signing_input, signature) = token
+            .trim()
+            .rsplit_once('.')
+            .ok_or_else(|| WebhookError::Malformed("expected three segments".into()))?;
+        let (header, payload) = signing_input
+            .split_once('.')
+            .filter(|(_, payload)| !payload.contains('.'))
+            .ok_or_else(|| WebhookError::Malformed("expected three segments".into()))?;
+
+        let header: JwtHeader = serde_json::from_slice(&decode_segment(header)?)?;
+        if header.alg != "ES256" {
+            return Err(WebhookError::UnsupportedAlgorithm(header.alg.into()));
+        }
+        let signature = Signature::from_slice(&decode_segment(signature)?)
+            .map_err(|_| WebhookError::InvalidSignature)?;
+        self.verify_signature(header.kid.as_deref(), signing_input.as_bytes(), &signature)?;
+
+        let payload = decode_segment(payload)?;
+        let claims: JwtClaims = serde_json::from_slice(&payload)?;
+        let now = now.timestamp();
+        let leeway = self.leeway.num_seconds();
+        let exp = claims.exp.ok_or(WebhookError::MissingClaim("exp"))?;
+        if exp + leeway <= now {
+            return Err(WebhookError::Expired);
+        }
+        let iat = claims.iat.ok_or(WebhookError::MissingClaim("iat"))?;
+        if iat - leeway > now {
+            return Err(WebhookError::IssuedInFuture);
+        }
+
+        let merchant_id = claims
+            .merchant_id
+            .ok_or(WebhookError::MissingClaim("merchantId"))?;
+        if *merchant_id != *self.merchant_id {
+            return Err(WebhookError::MerchantMismatch(merchant_id.into()));
+        }
+        Ok(serde_json::from_slice(&payload)?)
+    }
+
+    fn verify_signature(
+        &self,
+        kid: Option<&str>,
+        message: &[u8],
+        signature: &Signature,
+    ) -> R<(), WebhookError> {
+        let candidates: Vec<&Jwk> = match kid {
+            Some(kid) => self
+                .jwks
+                .keys
+                .iter()
+                .filter(|key| key.kid.as_deref() == Some(kid))
+                .collect(),
+            None => self.jwks.keys.iter().collect(),
+        };
+        if candidates.is_empty() {
+            return Err(WebhookError::UnknownKey(kid.map(Into::into)));
+        }
+        for key in candidates {
+            if key.verifying_key()?.verify(message, signature).is_ok() {
+                return Ok(());
+            }
+        }
+        Err(WebhookError::InvalidSignature)
+    }
+}
+
+fn decode_segment(segment: &str) -> R<Vec<u8>, WebhookError> {
+    URL_SAFE_NO_PAD
+        .decode(segment)
+        .map_err(|err| WebhookError::Malformed(err.to_string().into()))
+}
+
+/// Уведомление Yandex Pay
+/// <https://pay.yandex.ru/docs/ru/custom/backend/merchant-api/webhook#body>
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookNotification {
+    /// Идентификатор продавца
+    pub merchant_id: String,
+    #[serde(with = "iso8601")]
+    /// Дата и время события
+    pub event_time: Time,
+    #[serde(flatten)]
+    /// Событие
+    pub event: WebhookEvent,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "SCREAMING_SNAKE_CASE")]
+/// Тип события и его данные
+pub enum WebhookEvent {
+    /// Изменение статуса заказа
+    OrderStatusUpdated { order: OrderWebhookData },
+    /// Изменение статуса операции
+    OperationStatusUpdated { operation: OperationWebhookData },
+    /// Изменение статуса подписки
+    SubscriptionStatusUpdated {
+        subscription: SubscriptionWebhookData,
+    },
+    /// Событие, неизвестное этой версии крейта
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+/// <https://pay.yandex.ru/docs/ru/custom/backend/merchant-api/webhook#order>
+pub struct OrderWebhookData {
+    /// Id заказа на стороне продавца
+    pub order_id: String,
+    /// Статус оплаты
+    pub payment_status: PaymentStatus,
+    #[serde(default)]
+    /// Была ли изменена корзина
+    pub cart_updated: Option<bool>,
+    #[serde(default)]
+    /// Статус доставки
+    pub delivery_status: Option<DeliveryStatus>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+/// <https://pay.yandex.ru/docs/ru/custom/backend/merchant-api/webhook#operation>
+pub struct OperationWebhookData {
+    /// Уникальный идентификатор операции
+    pub operation_id: String,
+    /// Id заказа на стороне продавца
+    pub order_id: String,
+    /// Тип операции
+    pub operation_type: OperationType,
+    /// Статус операции
+    pub status: OperationStatus,
+    #[serde(default)]
+    /// Идентификатор операции на стороне продавца
+    pub external_operation_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+/// <https://pay.yandex.ru/docs/ru/custom/backend/merchant-api/webhook#subscription>
+pub struct SubscriptionWebhookData {
+    /// Идентификатор подписки
+    pub customer_subscription_id: String,
+    /// Статус подписки
+    pub status: SubscriptionStatus,
+    #[serde(default)]
+    /// Идентификатор плана подписки
+    pub subscription_plan_id: Option<String>,
+    #[serde(default, with = "option_iso8601")]
+    /// Дата следующего списания
+    pub next_write_off: Option<Time>,
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use p256::ecdsa::SigningKey;
+    use p256::ecdsa::signature::Signer;
+
+    const MERCHANT_ID: &str = "merchant";
+
+    pub(crate) fn signing_key(seed: u8) -> SigningKey {
+        SigningKey::from_bytes(&[seed; 32].into()).unwrap()
+    }
+
+    pub(crate) fn jwk(key: &SigningKey, kid: &str) -> Jwk {
+        let point = key.verifying_key().to_encoded_point(false);
+        Jwk {
+            kty: "EC".into(),
+            kid: Some(kid.into()),
+            alg: Some("ES256".into()),
+            crv: Some("P-256".into()),
+            x: Some(URL_SAFE_NO_PAD.encode(point.x().unwrap())),
+            y: Some(URL_SAFE_NO_PAD.encode(point.y().unwrap())),
+        }
+    }
+
+    pub(crate) fn sign(key: &SigningKey, kid: &str, payload: &serde_json::Value) -> String {
+        let header = serde_json::json!({ "alg": "ES256", "kid": kid, "typ": "JWT" });
+        let input = format!(
+            "{}.{}",
+            URL_SAFE_NO_PAD.encode(header.to_string()),
+            URL_SAFE_NO_PAD.encode(payload.to_string())
+        );
+        let signature: Signature = key.sign(input.as_bytes());
+        format!("{input}.{}", URL_SAFE_NO_PAD.encode(signature.to_bytes()))
+    }
+
+    fn now() -> Time {
+        chrono::DateTime::from_timestamp(1_750_000_000, 0).unwrap()
+    }
+
+    fn payload(event: serde_json::Value) -> serde_json::Value {
+        let mut payload = serde_json::json!({
+            "merchantId": MERCHANT_ID,
+            "eventTime": "2025-06-15T15:06:40Z",
+            "iat": now().timestamp(),
+            "exp": now().timestamp() + 300,
+        });
+        payload
+            .as_object_mut()
+            .unwrap()
+            .extend(event.as_object().unwrap().clone());
+        payload
+    }
+
+    fn verifier(key: &SigningKey) -> WebhookVerifier {
+        WebhookVerifier::new(
+            Jwks {
+                keys: vec![jwk(&signing_key(7), "other"), jwk(key, "key-1")],
+            },
+            MERCHANT_ID.into(),
+        )
+    }
+
+    #[test]
+    fn test_verify_order_status_updated() {
+        let key = signing_key(1);
+        let token = sign(
+            &key,
+            "key-1",
+            &payload(serde_json::json!({
+                "event": "ORDER_STATUS_UPDATED",
+                "order": { "orderId": "order-1", "paymentStatus": "CAPTURED", "cartUpdated": false }
+            })),
+        );
+        let notification = verifier(&key).verify_at(&token, now()).unwrap();
+        assert_eq!(notification.merchant_id, MERCHANT_ID);
+        match notification.event {
+            WebhookEvent::OrderStatusUpdated { order } => {
+                assert_eq!(order.order_id, "order-1");
+                assert_eq!(order.payment_status, PaymentStatus::Captured);
+            }
+            event => panic!("unexpected event {event:?}"),
+        }
+    }
+
+    #[test]
+    fn test_verify_operation_and_subscription_events() {
+        let key = signing_key(1);
+        let token = sign(
+            &key,
+            "key-1",
+            &payload(serde_json::json!({
+                "event": "OPERATION_STATUS_UPDATED",
+                "operation": {
+                    "operationId": "op-1",
+                    "orderId": "order-1",
+                    "operationType": "REFUND",
+                    "status": "SUCCESS",
+                    "externalOperationId": "ext-1"
+                }
+            })),
+        );
+        let notification = verifier(&key).verify_at(&token, now()).unwrap();
+        let WebhookEvent::OperationStatusUpdated { operation } = notification.event else {
+            panic!("expected operation event");
+        };
+        assert_eq!(operation.operation_type, OperationType::Refund);
+        assert_eq!(operation.status, OperationStatus::Success);
+
+        let token = sign(
+            &key,
+            "key-1",
+            &payload(serde_json::json!({
+                "event": "SUBSCRIPTION_STATUS_UPDATED",
+                "subscription": {
+                    "customerSubscriptionId": "sub-1",
+                    "status": "ACTIVE",
+                    "nextWriteOff": "2025-07-15T00:00:00Z"
+                }
+            })),
+        );
+        let notification = verifier(&key).verify_at(&token, now()).unwrap();
+        let WebhookEvent::SubscriptionStatusUpdated { subscription } = notification.event else {
+            panic!("expected subscription event");
+        };
+        assert_eq!(subscription.status, SubscriptionStatus::Active);
+        assert!(subscription.next_write_off.is_some());
+    }
+
+    #[test]
+    fn test_verify_unknown_values() {
+        let key = signing_key(1);
+        let verify = |event: serde_json::Value| {
+            let token = sign(&key, "key-1", &payload(event));
+            verifier(&key).verify_at(&token, now()).unwrap().event
+        };
+
+        let event = verify(serde_json::json!({
+            "event": "ORDER_STATUS_UPDATED",
+            "order": {
+                "orderId": "order-1",
+                "paymentStatus": "ON_HOLD",
+                "deliveryStatus": "LOST_IN_SPACE"
+            }
+        }));
+        let WebhookEvent::OrderStatusUpdated { order } = event else {
+            panic!("expected order event");
+        };
+        assert_eq!(
+            order.payment_status,
+            PaymentStatus::Unknown("ON_HOLD".into())
+        );
+        assert_eq!(
+            order.delivery_status,
+            Some(DeliveryStatus::Unknown("LOST_IN_SPACE".into()))
+        );
+
+        let event = verify(serde_json::json!({
+            "event": "OPERATION_STATUS_UPDATED",
+            "operation": {
+                "operationId": "op-1",
+                "orderId": "order-1",
+                "operationType": "CHARGEBACK",
+                "status": "DISPUTED"
+            }
+        }));
+        let WebhookEvent::OperationStatusUpdated { operation } = event else {
+            panic!("expected operation event");
+        };
+        assert_eq!(
+            operation.operation_type,
+            OperationType::Unknown("CHARGEBACK".into())
+        );
+        assert_eq!(
+            operation.status,
+            OperationStatus::Unknown("DISPUTED".into())
+        );
+
+        let event = verify(serde_json::json!({
+            "event": "SUBSCRIPTION_STATUS_UPDATED",
+            "subscription": {"customerSubscriptionId": "sub-1", "status": "PAUSED"}
+        }));
+        let WebhookEvent::SubscriptionStatusUpdated { subscription } = event else {
+            panic!("expected subscription event");
+        };
+        assert_eq!(
+            subscription.status,
+            SubscriptionStatus::Unknown("PAUSED".into())
+        );
+
+        let event = verify(serde_json::json!({
+            "event": "CHARGEBACK_OPENED",
+            "chargeback": {"id": "cb-1"}
+        }));
+        assert!(matches!(event, WebhookEvent::Unknown));
+    }
+
+    #[test]
+    fn test_reject_invalid_tokens() {
+        let key = signing_key(1);
+        let event = serde_json::json!({
+            "event": "ORDER_STATUS_UPDATED",
+            "order": { "orderId": "order-1", "paymentStatus": "AUTHORIZED" }
+        });
+        let verifier = verifier(&key);
+
+        let forged = sign(&signing_key(2), "key-1", &payload(event.clone()));
+        assert!(matches!(
+            verifier.verify_at(&forged, now()),
+            Err(WebhookError::InvalidSignature)
+        ));
+
+        let unknown = sign(&key, "missing", &payload(event.clone()));
+        assert!(matches!(
+            verifier.verify_at(&unknown, now()),
+            Err(WebhookError::UnknownKey(_))
+        ));
+
+        let token = sign(&key, "key-1", &payload(event.clone()));
+        let later = now() + chrono::Duration::seconds(600);
+        assert!(matches!(
+            verifier.verify_at(&token, later),
+            Err(WebhookError::Expired)
+        ));
+        let earlier = now() - chrono::Duration::seconds(600);
+        assert!(matches!(
+            verifier.verify_at(&token, earlier),
+            Err(WebhookError::IssuedInFuture)
+        ));
+
+        let mut other_merchant = payload(event);
+        other_merchant["merchantId"] = "someone-else".into();
+        let token = sign(&key, "key-1", &other_merchant);
+        assert!(matches!(
+            verifier.verify_at(&token, now()),
+            Err(WebhookError::MerchantMismatch(_))
+        ));
+
+        assert!(matches!(
+            verifier.verify_at("not-a-jwt", now()),
+            Err(WebhookError::Malformed(_))
+        ));
+    }
+}